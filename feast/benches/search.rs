@@ -0,0 +1,83 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+
+use feast::core::*;
+use feast::input::{find_byte, find_byte3, find_bytes};
+use feast::pass::{SlicePass, SlicePassContext, VerboseError};
+
+type BenchError = VerboseError<'static, SlicePassContext<'static, u8>>;
+type BenchPass = SlicePass<'static, u8, BenchError>;
+
+const SIZES: [usize; 3] = [64, 4 * 1024, 1024 * 1024];
+
+/// Log-like text with the byte searched for only at the very end.
+fn haystack(len: usize, end: &[u8]) -> &'static [u8] {
+    let mut bytes = b"GET /index.html 200 "
+        .iter()
+        .copied()
+        .cycle()
+        .take(len)
+        .collect::<Vec<_>>();
+    bytes.extend_from_slice(end);
+    Box::leak(bytes.into_boxed_slice())
+}
+
+fn bench_find_byte(c: &mut Criterion) {
+    let mut group = c.benchmark_group("find_byte");
+    for &len in &SIZES {
+        let bytes = haystack(len, b"\n");
+        group.throughput(Throughput::Bytes(bytes.len() as u64));
+        group.bench_with_input(BenchmarkId::new("word", len), bytes, |b, bytes| {
+            b.iter(|| find_byte(b'\n', black_box(bytes)))
+        });
+        group.bench_with_input(BenchmarkId::new("iter", len), bytes, |b, bytes| {
+            b.iter(|| black_box(bytes).iter().position(|&byte| byte == b'\n'))
+        });
+        group.bench_with_input(BenchmarkId::new("word3", len), bytes, |b, bytes| {
+            b.iter(|| find_byte3(b'\r', b'\n', b';', black_box(bytes)))
+        });
+    }
+    group.finish();
+}
+
+fn bench_find_bytes(c: &mut Criterion) {
+    let mut group = c.benchmark_group("find_bytes");
+    for &len in &SIZES {
+        let bytes = haystack(len, b"-->");
+        group.throughput(Throughput::Bytes(bytes.len() as u64));
+        group.bench_with_input(BenchmarkId::new("word", len), bytes, |b, bytes| {
+            b.iter(|| find_bytes(b"-->", black_box(bytes)))
+        });
+        group.bench_with_input(BenchmarkId::new("windows", len), bytes, |b, bytes| {
+            b.iter(|| {
+                black_box(bytes)
+                    .windows(3)
+                    .position(|window| window == b"-->")
+            })
+        });
+    }
+    group.finish();
+}
+
+fn bench_take_till(c: &mut Criterion) {
+    let mut group = c.benchmark_group("take_till");
+    for &len in &SIZES {
+        let bytes = haystack(len, b"\n");
+        group.throughput(Throughput::Bytes(bytes.len() as u64));
+        group.bench_with_input(BenchmarkId::new("byte", len), bytes, |b, _| {
+            let parser = take_till(b'\n');
+            b.iter(|| parser(BenchPass::from(black_box(bytes))).is_ok())
+        });
+        group.bench_with_input(BenchmarkId::new("closure", len), bytes, |b, _| {
+            let parser = take_till(|byte: &u8| *byte == b'\n');
+            b.iter(|| parser(BenchPass::from(black_box(bytes))).is_ok())
+        });
+        group.bench_with_input(BenchmarkId::new("take_until", len), bytes, |b, _| {
+            let parser = take_until(b"\n");
+            b.iter(|| parser(BenchPass::from(black_box(bytes))).is_ok())
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_find_byte, bench_find_bytes, bench_take_till);
+criterion_main!(benches);
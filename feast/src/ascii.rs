@@ -1,5 +1,5 @@
-use crate::input::Input;
-use crate::pass::{Pass, PassInput, PassResult};
+use crate::input::{Capture, ExpectedHint, Input, Requirement, TokenTag, Unexpected};
+use crate::pass::{Pass, PassInput, PassInputError, PassResult, PassSection};
 
 use crate::core::*;
 
@@ -75,6 +75,117 @@ where
     )(pass)
 }
 
+/// Consumes a byte if it is ASCII whitespace `[ \t\n\x0C\r]`.
+pub fn ascii_whitespace<'i, P>(pass: P) -> PassResult<'i, P, u8>
+where
+    P: Pass<'i>,
+    PassInput<'i, P>: Input<'i, Token = u8>,
+{
+    hint(
+        take_token_if(u8::is_ascii_whitespace),
+        "valid ascii whitespace",
+    )(pass)
+}
+
+/// Consumes a byte if it is ASCII punctuation, such as `!`, `{` or `~`.
+pub fn ascii_punctuation<'i, P>(pass: P) -> PassResult<'i, P, u8>
+where
+    P: Pass<'i>,
+    PassInput<'i, P>: Input<'i, Token = u8>,
+{
+    hint(
+        take_token_if(u8::is_ascii_punctuation),
+        "valid ascii punctuation",
+    )(pass)
+}
+
+/// Consumes a byte if it is an ASCII graphic character `[!-~]`.
+pub fn ascii_graphic<'i, P>(pass: P) -> PassResult<'i, P, u8>
+where
+    P: Pass<'i>,
+    PassInput<'i, P>: Input<'i, Token = u8>,
+{
+    hint(take_token_if(u8::is_ascii_graphic), "valid ascii graphic")(pass)
+}
+
+/// Consumes a byte if it is an ASCII control character `[\x00-\x1F\x7F]`.
+pub fn ascii_control<'i, P>(pass: P) -> PassResult<'i, P, u8>
+where
+    P: Pass<'i>,
+    PassInput<'i, P>: Input<'i, Token = u8>,
+{
+    hint(take_token_if(u8::is_ascii_control), "valid ascii control")(pass)
+}
+
+/// Consumes zero or more bytes of ASCII whitespace.
+pub fn multispace0<'i, P>(pass: P) -> PassResult<'i, P, PassSection<'i, P>>
+where
+    P: Pass<'i>,
+    PassInput<'i, P>: Input<'i, Token = u8>,
+{
+    take_while(u8::is_ascii_whitespace)(pass)
+}
+
+/// Consumes one or more bytes of ASCII whitespace.
+pub fn multispace1<'i, P>(pass: P) -> PassResult<'i, P, PassSection<'i, P>>
+where
+    P: Pass<'i>,
+    PassInput<'i, P>: Input<'i, Token = u8>,
+{
+    hint(
+        take_while1(u8::is_ascii_whitespace),
+        "valid ascii whitespace",
+    )(pass)
+}
+
+/// Consumes a line ending, either `\n` or `\r\n`.
+pub fn line_ending<'i, P>(pass: P) -> PassResult<'i, P, PassSection<'i, P>>
+where
+    P: Pass<'i>,
+    PassInput<'i, P>: Input<'i, Token = u8>,
+{
+    let input = pass.input();
+    let mut tokens = input.iter();
+    let len = match (tokens.next(), tokens.next()) {
+        (Some(b'\n'), _) => 1,
+        (Some(b'\r'), Some(b'\n')) => 2,
+        (None, _) | (Some(b'\r'), None) => {
+            return Err(pass.with_input_error_incomplete(Requirement::Exact(1)));
+        }
+        (Some(b'\r'), Some(token)) | (Some(token), _) => {
+            return Err(pass.with_input_error_unexpected(Unexpected {
+                unexpected: TokenTag::Token(token),
+                expecting: ExpectedHint::Description("line ending"),
+            }));
+        }
+    };
+    let ((taken, rest), pass) = pass.with_input_result(input.split_at(len))?;
+    Ok((taken, pass.commit(rest)))
+}
+
+/// Consumes a tag, ignoring ASCII case.
+pub fn tag_no_case<'i, P>(tag: &'i [u8]) -> impl Fn(P) -> PassResult<'i, P, PassSection<'i, P>>
+where
+    P: Pass<'i>,
+    PassInput<'i, P>: Input<'i, Token = u8>,
+{
+    move |pass: P| {
+        let input = pass.input();
+        let ((input_tag, rest), pass) = pass.with_input_result(input.split_at(tag.len()))?;
+        let mismatch = input_tag
+            .iter()
+            .zip(tag)
+            .find(|(token, expected)| !token.eq_ignore_ascii_case(expected));
+        if let Some((token, _)) = mismatch {
+            return Err(pass.with_input_error_unexpected(Unexpected {
+                unexpected: TokenTag::Token(token),
+                expecting: ExpectedHint::Tag(tag),
+            }));
+        }
+        Ok((input_tag, pass.commit(rest)))
+    }
+}
+
 /// Parses a ASCII decimal digit.
 pub fn parse_ascii_digit<'i, P>(pass: P) -> PassResult<'i, P, u8>
 where
@@ -84,10 +195,326 @@ where
     map(ascii_digit, |t| t - b'0')(pass)
 }
 
+/// Parses an integer of ASCII digits in a radix, with an optional sign if
+/// `signed`, returning whether it is negative and its magnitude.
+///
+/// The magnitude may be at most `max`, or one more if negative. A digit that
+/// would exceed it is unexpected, as is a missing first digit.
+fn ascii_int<'i, P>(
+    pass: P,
+    radix: u32,
+    signed: bool,
+    max: u128,
+    description: &'static str,
+) -> PassResult<'i, P, (bool, u128)>
+where
+    P: Pass<'i>,
+    PassInput<'i, P>: Input<'i, Token = u8>,
+{
+    let unexpected = |pass: P, token: u8| {
+        pass.with_input_error_unexpected(Unexpected {
+            unexpected: TokenTag::Token(token),
+            expecting: ExpectedHint::Description(description),
+        })
+    };
+    let mut input = pass.input();
+    let mut negative = false;
+    if signed {
+        if let Ok((sign @ (b'+' | b'-'), rest)) =
+            input.clone().split_first::<PassInputError<'i, P>>()
+        {
+            negative = sign == b'-';
+            input = rest;
+        }
+    }
+    let limit = if negative { max + 1 } else { max };
+    let mut magnitude = 0u128;
+    let mut digits = 0;
+    loop {
+        let (token, rest) = match input.clone().split_first::<PassInputError<'i, P>>() {
+            Ok(split) => split,
+            Err(_) if digits > 0 && input.is_complete() => break,
+            Err(_) => {
+                let requirement = if digits == 0 {
+                    Requirement::Exact(1)
+                } else {
+                    Requirement::AtLeast(1)
+                };
+                return Err(pass.with_input_error_incomplete(requirement));
+            }
+        };
+        let digit = match char::from(token).to_digit(radix) {
+            Some(digit) => digit,
+            None if digits > 0 => break,
+            None => return Err(unexpected(pass, token)),
+        };
+        magnitude = match magnitude
+            .checked_mul(u128::from(radix))
+            .and_then(|magnitude| magnitude.checked_add(u128::from(digit)))
+        {
+            Some(magnitude) if magnitude <= limit => magnitude,
+            _ => return Err(unexpected(pass, token)),
+        };
+        digits += 1;
+        input = rest;
+    }
+    Ok(((negative, magnitude), pass.commit(input)))
+}
+
+macro_rules! impl_int_parsers {
+    ($($ty:ident => $signed:literal, $dec:ident, $hex:ident, $oct:ident, $bin:ident;)*) => {
+        $(
+            impl_int_parsers!(@radix $ty, $signed, $dec, 10, "decimal");
+            impl_int_parsers!(@radix $ty, $signed, $hex, 16, "hexadecimal");
+            impl_int_parsers!(@radix $ty, $signed, $oct, 8, "octal");
+            impl_int_parsers!(@radix $ty, $signed, $bin, 2, "binary");
+        )*
+    };
+    (@radix $ty:ident, $signed:literal, $name:ident, $radix:literal, $kind:literal) => {
+        #[doc = concat!(
+            "Parses a `",
+            stringify!($ty),
+            "` from ASCII ",
+            $kind,
+            " digits.\n\nValues out of range are unexpected at the digit that overflows."
+        )]
+        pub fn $name<'i, P>(pass: P) -> PassResult<'i, P, $ty>
+        where
+            P: Pass<'i>,
+            PassInput<'i, P>: Input<'i, Token = u8>,
+        {
+            let ((negative, magnitude), pass) = ascii_int(
+                pass,
+                $radix,
+                $signed,
+                $ty::MAX as u128,
+                concat!($kind, " ", stringify!($ty)),
+            )?;
+            let value = if negative {
+                (magnitude as i128).wrapping_neg() as $ty
+            } else {
+                magnitude as $ty
+            };
+            Ok((value, pass))
+        }
+    };
+}
+
+impl_int_parsers! {
+    u8 => false, dec_u8, hex_u8, oct_u8, bin_u8;
+    u16 => false, dec_u16, hex_u16, oct_u16, bin_u16;
+    u32 => false, dec_u32, hex_u32, oct_u32, bin_u32;
+    u64 => false, dec_u64, hex_u64, oct_u64, bin_u64;
+    u128 => false, dec_u128, hex_u128, oct_u128, bin_u128;
+    i8 => true, dec_i8, hex_i8, oct_i8, bin_i8;
+    i16 => true, dec_i16, hex_i16, oct_i16, bin_i16;
+    i32 => true, dec_i32, hex_i32, oct_i32, bin_i32;
+    i64 => true, dec_i64, hex_i64, oct_i64, bin_i64;
+    i128 => true, dec_i128, hex_i128, oct_i128, bin_i128;
+}
+
+/// Whether a float may be written as a special value.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FloatSpecials {
+    /// Only finite numbers are accepted.
+    Reject,
+    /// `inf`, `infinity` and `nan` are also accepted, ignoring case.
+    Accept,
+}
+
+/// Scans tokens for the longest prefix that is a float.
+struct FloatScanner<I> {
+    tokens: I,
+    /// The number of tokens before the current one.
+    len: usize,
+    /// The length of the longest float scanned so far.
+    accepted: usize,
+    current: Option<u8>,
+}
+
+impl<I> FloatScanner<I>
+where
+    I: Iterator<Item = u8>,
+{
+    fn new(mut tokens: I) -> Self {
+        let current = tokens.next();
+        Self {
+            tokens,
+            len: 0,
+            accepted: 0,
+            current,
+        }
+    }
+
+    fn advance(&mut self) {
+        self.len += 1;
+        self.current = self.tokens.next();
+    }
+
+    fn advance_if(&mut self, pred: impl Fn(u8) -> bool) -> bool {
+        match self.current {
+            Some(token) if pred(token) => {
+                self.advance();
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn digits(&mut self) -> usize {
+        let start = self.len;
+        while self.advance_if(|token| token.is_ascii_digit()) {}
+        self.len - start
+    }
+
+    /// Scans `inf`, `infinity` or `nan`, ignoring case, returning whether
+    /// the current token starts one.
+    ///
+    /// If only part of a word matches, the scanner is left at its start, so
+    /// the start is reported rather than where the match stopped.
+    fn special(&mut self) -> bool {
+        let word: &[u8] = match self.current.map(|token| token.to_ascii_lowercase()) {
+            Some(b'i') => b"infinity",
+            Some(b'n') => b"nan",
+            _ => return false,
+        };
+        let (len, current) = (self.len, self.current);
+        for (i, expected) in word.iter().enumerate() {
+            if !self.advance_if(|token| token.eq_ignore_ascii_case(expected)) {
+                break;
+            }
+            if i == 2 || i == word.len() - 1 {
+                self.accepted = self.len;
+            }
+        }
+        if self.accepted == 0 && self.current.is_some() {
+            self.len = len;
+            self.current = current;
+        }
+        true
+    }
+
+    fn scan(&mut self, specials: FloatSpecials) {
+        self.advance_if(|token| token == b'+' || token == b'-');
+        // Digits cannot follow the start of a special word.
+        if specials == FloatSpecials::Accept && self.special() {
+            return;
+        }
+        let mut mantissa = self.digits();
+        if mantissa > 0 {
+            self.accepted = self.len;
+        }
+        if self.advance_if(|token| token == b'.') {
+            mantissa += self.digits();
+            if mantissa > 0 {
+                self.accepted = self.len;
+            }
+        }
+        if mantissa > 0 && self.advance_if(|token| token == b'e' || token == b'E') {
+            self.advance_if(|token| token == b'+' || token == b'-');
+            if self.digits() > 0 {
+                self.accepted = self.len;
+            }
+        }
+    }
+}
+
+/// Recognises a float without converting it, returning its section.
+///
+/// Floats have an optional sign, then digits with an optional fraction, at
+/// least one digit in all, then an optional exponent. A float that reaches
+/// the end of an incomplete input requires more input, as it may continue.
+pub fn recognize_float<'i, P>(
+    specials: FloatSpecials,
+) -> impl Fn(P) -> PassResult<'i, P, PassSection<'i, P>>
+where
+    P: Pass<'i>,
+    PassInput<'i, P>: Input<'i, Token = u8>,
+{
+    move |pass: P| {
+        let input = pass.input();
+        let mut scanner = FloatScanner::new(input.iter());
+        scanner.scan(specials);
+        match scanner.current {
+            None if !input.is_complete() => {
+                return Err(pass.with_input_error_incomplete(Requirement::AtLeast(1)));
+            }
+            None if scanner.accepted == 0 => {
+                return Err(pass.with_input_error_incomplete(Requirement::Exact(1)));
+            }
+            Some(token) if scanner.accepted == 0 => {
+                return Err(pass.with_input_error_unexpected(Unexpected {
+                    unexpected: TokenTag::Token(token),
+                    expecting: ExpectedHint::Description("valid float"),
+                }));
+            }
+            _ => {}
+        }
+        let ((taken, rest), pass) = pass.with_input_result(input.split_at(scanner.accepted))?;
+        Ok((taken, pass.commit(rest)))
+    }
+}
+
+macro_rules! impl_float_parsers {
+    ($($name:ident => $ty:ident;)*) => {
+        $(
+            #[doc = concat!(
+                "Parses a `",
+                stringify!($ty),
+                "` from ASCII, rounding exactly as the standard library does.\n\n",
+                "See [`recognize_float`] for the accepted format."
+            )]
+            pub fn $name<'i, P>(specials: FloatSpecials) -> impl Fn(P) -> PassResult<'i, P, $ty>
+            where
+                P: Pass<'i>,
+                PassInput<'i, P>: Input<'i, Token = u8>,
+            {
+                let recognize = recognize_float(specials);
+                move |pass: P| {
+                    let input = pass.input();
+                    let (section, pass) = recognize(pass)?;
+                    // The section is ASCII, so borrowed tokens are parsed in
+                    // place and only scattered tokens are copied.
+                    let parsed = match section.as_contiguous() {
+                        Some(bytes) => std::str::from_utf8(bytes)
+                            .ok()
+                            .and_then(|text| text.parse::<$ty>().ok()),
+                        None => section
+                            .iter()
+                            .map(char::from)
+                            .collect::<String>()
+                            .parse::<$ty>()
+                            .ok(),
+                    };
+                    match parsed {
+                        Some(value) => Ok((value, pass)),
+                        None => {
+                            let unexpected = match section.iter().next() {
+                                Some(token) => TokenTag::Token(token),
+                                None => TokenTag::Tag(&[]),
+                            };
+                            Err(pass.commit(input).with_input_error_unexpected(Unexpected {
+                                unexpected,
+                                expecting: ExpectedHint::Description("valid float"),
+                            }))
+                        }
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_float_parsers! {
+    float_f32 => f32;
+    float_f64 => f64;
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::pass::{SlicePass, SlicePassContext, VerboseError};
+    use crate::input::ErrorReason;
+    use crate::pass::{Position, SlicePass, SlicePassContext, VerboseError};
 
     use assert_matches::assert_matches;
 
@@ -121,4 +548,231 @@ mod tests {
             }
         );
     }
+
+    #[test]
+    fn test_multispace() {
+        assert_matches!(
+            multispace0(test_pass(b" \t\r\nx")),
+            Ok((out, pass_out)) => {
+                assert_eq!(out, (&b" \t\r\n"[..]).into());
+                assert_eq!(pass_out, test_pass(b"x"));
+            }
+        );
+        assert_matches!(
+            multispace0(test_pass(b"x")),
+            Ok((out, _)) => assert!(out.0.is_empty())
+        );
+        assert_matches!(
+            multispace1(test_pass(b"x")),
+            Err((err, _)) => {
+                assert_eq!(err.reason(), &ErrorReason::Unexpected(Unexpected {
+                    unexpected: TokenTag::Token(b'x'),
+                    expecting: ExpectedHint::Description("valid ascii whitespace"),
+                }));
+            }
+        );
+    }
+
+    #[test]
+    fn test_line_ending() {
+        assert_matches!(
+            line_ending(test_pass(b"\r\nx")),
+            Ok((out, pass_out)) => {
+                assert_eq!(out, (&b"\r\n"[..]).into());
+                assert_eq!(pass_out, test_pass(b"x"));
+            }
+        );
+        assert_matches!(line_ending(test_pass(b"\n")), Ok(_));
+        assert_matches!(
+            line_ending(test_pass(b"\r")),
+            Err((err, _)) => {
+                assert_eq!(err.reason(), &ErrorReason::Incomplete(Requirement::Exact(1)));
+            }
+        );
+        assert_matches!(
+            line_ending(test_pass(b"\rx")),
+            Err((err, _)) => {
+                assert_eq!(err.reason(), &ErrorReason::Unexpected(Unexpected {
+                    unexpected: TokenTag::Token(b'x'),
+                    expecting: ExpectedHint::Description("line ending"),
+                }));
+            }
+        );
+    }
+
+    #[test]
+    fn test_character_classes() {
+        assert_matches!(ascii_punctuation(test_pass(b"{")), Ok((b'{', _)));
+        assert_matches!(ascii_punctuation(test_pass(b"a")), Err(_));
+        assert_matches!(ascii_graphic(test_pass(b"~")), Ok((b'~', _)));
+        assert_matches!(ascii_graphic(test_pass(b" ")), Err(_));
+        assert_matches!(ascii_control(test_pass(b"\x7f")), Ok((0x7f, _)));
+        assert_matches!(ascii_whitespace(test_pass(b"\x0c")), Ok((0x0c, _)));
+    }
+
+    #[test]
+    fn test_tag_no_case() {
+        assert_matches!(
+            tag_no_case(b"get")(test_pass(b"GeT /")),
+            Ok((out, pass_out)) => {
+                assert_eq!(out, (&b"GeT"[..]).into());
+                assert_eq!(pass_out, test_pass(b" /"));
+            }
+        );
+        assert_matches!(
+            tag_no_case(b"get")(test_pass(b"GOT")),
+            Err((err, _)) => {
+                assert_eq!(err.reason(), &ErrorReason::Unexpected(Unexpected {
+                    unexpected: TokenTag::Token(b'O'),
+                    expecting: ExpectedHint::Tag(b"get"),
+                }));
+            }
+        );
+    }
+
+    #[test]
+    fn test_unsigned_ints() {
+        assert_matches!(
+            dec_u8(test_pass(b"255;")),
+            Ok((255, pass_out)) => assert_eq!(pass_out, test_pass(b";"))
+        );
+        assert_matches!(hex_u32(test_pass(b"DeadBeef")), Ok((0xdead_beef, _)));
+        assert_matches!(oct_u16(test_pass(b"777")), Ok((0o777, _)));
+        assert_matches!(bin_u8(test_pass(b"1012")), Ok((0b101, _)));
+        assert_matches!(
+            dec_u128(test_pass(b"340282366920938463463374607431768211455")),
+            Ok((u128::MAX, _))
+        );
+    }
+
+    #[test]
+    fn test_signed_ints() {
+        assert_matches!(dec_i8(test_pass(b"-128")), Ok((-128, _)));
+        assert_matches!(dec_i8(test_pass(b"+127")), Ok((127, _)));
+        assert_matches!(hex_i16(test_pass(b"-7fff")), Ok((-0x7fff, _)));
+        assert_matches!(
+            dec_i128(test_pass(b"-170141183460469231731687303715884105728")),
+            Ok((i128::MIN, _))
+        );
+        assert_matches!(
+            dec_u8(test_pass(b"-1")),
+            Err((err, _)) => {
+                assert_eq!(err.reason(), &ErrorReason::Unexpected(Unexpected {
+                    unexpected: TokenTag::Token(b'-'),
+                    expecting: ExpectedHint::Description("decimal u8"),
+                }));
+            }
+        );
+    }
+
+    #[test]
+    fn test_int_overflow() {
+        assert_matches!(
+            dec_u8(test_pass(b"256")),
+            Err((err, pass_out)) => {
+                assert_eq!(pass_out, test_pass(b"256"));
+                assert_eq!(err.reason(), &ErrorReason::Unexpected(Unexpected {
+                    unexpected: TokenTag::Token(b'6'),
+                    expecting: ExpectedHint::Description("decimal u8"),
+                }));
+            }
+        );
+        assert_matches!(
+            dec_i8(test_pass(b"128")),
+            Err((err, _)) => assert!(err.to_string().contains("expected decimal i8, found '8'"))
+        );
+        assert_matches!(
+            dec_u8(test_pass(b"")),
+            Err((err, _)) => {
+                assert_eq!(err.reason(), &ErrorReason::Incomplete(Requirement::Exact(1)));
+            }
+        );
+    }
+
+    #[test]
+    fn test_recognize_float() {
+        let float = recognize_float(FloatSpecials::Reject);
+
+        for (text, rest) in &[
+            (&b"3.14,"[..], &b","[..]),
+            (b"-0.5e-3", b""),
+            (b"+.5", b""),
+            (b"5.", b""),
+            (b"1e", b"e"),
+            (b"2E+x", b"E+x"),
+            (b"inf", b"inf"),
+        ] {
+            let result = float(test_pass(text));
+            if rest.len() == text.len() {
+                assert_matches!(result, Err(_));
+            } else {
+                assert_matches!(result, Ok((_, pass_out)) => assert_eq!(pass_out, test_pass(rest)));
+            }
+        }
+
+        assert_matches!(
+            float(test_pass(b"-.e1")),
+            Err((err, _)) => {
+                assert_eq!(err.reason(), &ErrorReason::Unexpected(Unexpected {
+                    unexpected: TokenTag::Token(b'e'),
+                    expecting: ExpectedHint::Description("valid float"),
+                }));
+            }
+        );
+
+        let float = recognize_float(FloatSpecials::Accept);
+
+        for text in &[&b"i5;"[..], b"n1", b"in.5", b"-i2"] {
+            assert_matches!(float(test_pass(text)), Err((err, _)) => assert_eq!(err.position(), Position::new(0)));
+        }
+        assert_matches!(
+            float(test_pass(b"-in5")),
+            Err((err, _)) => {
+                assert_eq!(err.reason(), &ErrorReason::Unexpected(Unexpected {
+                    unexpected: TokenTag::Token(b'i'),
+                    expecting: ExpectedHint::Description("valid float"),
+                }));
+            }
+        );
+    }
+
+    #[test]
+    fn test_float_values() {
+        let double = float_f64(FloatSpecials::Reject);
+
+        assert_matches!(double(test_pass(b"0.1")), Ok((value, _)) => assert_eq!(value, 0.1));
+        assert_matches!(
+            double(test_pass(b"2.2250738585072014e-308")),
+            Ok((value, _)) => assert_eq!(value, f64::MIN_POSITIVE)
+        );
+        assert_matches!(
+            double(test_pass(b"1.7976931348623157e308")),
+            Ok((value, _)) => assert_eq!(value, f64::MAX)
+        );
+        assert_matches!(
+            float_f32(FloatSpecials::Reject)(test_pass(b"16777217")),
+            Ok((value, _)) => assert_eq!(value, 16777216.0)
+        );
+    }
+
+    #[test]
+    fn test_float_specials() {
+        let double = float_f64(FloatSpecials::Accept);
+
+        assert_matches!(
+            double(test_pass(b"-Infinity]")),
+            Ok((value, pass_out)) => {
+                assert_eq!(value, f64::NEG_INFINITY);
+                assert_eq!(pass_out, test_pass(b"]"));
+            }
+        );
+        assert_matches!(
+            double(test_pass(b"infx")),
+            Ok((value, pass_out)) => {
+                assert_eq!(value, f64::INFINITY);
+                assert_eq!(pass_out, test_pass(b"x"));
+            }
+        );
+        assert_matches!(double(test_pass(b"NaN")), Ok((value, _)) => assert!(value.is_nan()));
+    }
 }
@@ -0,0 +1,312 @@
+use std::ops::{BitOr, Shl};
+
+use crate::core::*;
+use crate::input::{
+    AsSlice, BitInput, Capture, ErrorReason, ExpectedHint, Input, Requirement, SliceInput,
+    TokenTag, Unexpected,
+};
+use crate::pass::{
+    BitPass, BitPassContext, Context, Error, Pass, PassError, PassInput, PassInputError,
+    PassResult, VerboseError,
+};
+
+/// The error type of bit passes run by [`bits`].
+pub type BitError<'i> = VerboseError<'i, BitPassContext<'i>>;
+
+/// Consumes a single bit as a `bool`.
+pub fn bit<'i, P>(pass: P) -> PassResult<'i, P, bool>
+where
+    P: Pass<'i>,
+    PassInput<'i, P>: Input<'i, Token = bool>,
+{
+    take_token()(pass)
+}
+
+/// Consumes `n` bits as an integer, most significant bit first.
+///
+/// Bits beyond the width of the integer are shifted out.
+pub fn take_bits<'i, P, O>(n: usize) -> impl Fn(P) -> PassResult<'i, P, O>
+where
+    P: Pass<'i>,
+    PassInput<'i, P>: Input<'i, Token = bool>,
+    O: From<bool> + Shl<usize, Output = O> + BitOr<Output = O>,
+{
+    move |pass: P| {
+        let (section, pass) = take_input(n)(pass)?;
+        let value = section
+            .iter()
+            .fold(O::from(false), |value, bit| (value << 1) | O::from(bit));
+        Ok((value, pass))
+    }
+}
+
+/// Skips any bits up to the next byte boundary.
+pub fn align<'i, P>(pass: P) -> PassResult<'i, P, ()>
+where
+    P: Pass<'i>,
+    P::Context: Context<'i, Input = BitInput<'i>>,
+{
+    let input = pass.input();
+    let skip = (8 - input.offset() % 8) % 8;
+    let ((_, rest), pass) = pass.with_input_result(input.split_at(skip))?;
+    Ok(((), pass.commit(rest)))
+}
+
+/// Converts a requirement in bits to one in bytes.
+///
+/// Bit inputs run by [`bits`] end on a byte boundary, so the bits missing
+/// always round up to whole bytes.
+fn bytes_required(requirement: &Requirement) -> Requirement {
+    match *requirement {
+        Requirement::Exact(n) => Requirement::Exact(n.div_ceil(8)),
+        Requirement::Between(min, max) if min.div_ceil(8) == max.div_ceil(8) => {
+            Requirement::Exact(min.div_ceil(8))
+        }
+        Requirement::Between(min, max) => Requirement::Between(min.div_ceil(8), max.div_ceil(8)),
+        Requirement::AtLeast(n) => Requirement::AtLeast(n.div_ceil(8)),
+        Requirement::Unknown => Requirement::Unknown,
+    }
+}
+
+fn byte_hint<'i>(hint: &ExpectedHint<'i, bool>) -> ExpectedHint<'i, u8> {
+    match hint {
+        ExpectedHint::None => ExpectedHint::None,
+        ExpectedHint::Token(true) => ExpectedHint::Description("set bit"),
+        ExpectedHint::Token(false) => ExpectedHint::Description("clear bit"),
+        ExpectedHint::Tag(_) => ExpectedHint::Description("bit pattern"),
+        ExpectedHint::Description(description) => ExpectedHint::Description(description),
+        ExpectedHint::Class(class) => ExpectedHint::Class(*class),
+        ExpectedHint::OneOf(hints) => ExpectedHint::OneOf(hints.iter().map(byte_hint).collect()),
+    }
+}
+
+/// Converts the reason for a bit error to an error on the byte pass.
+///
+/// Failures are cut again once converted, however deeply they are nested.
+fn byte_error<'i, P>(reason: &ErrorReason<'i, bool>, pass: P) -> (PassError<'i, P>, P)
+where
+    P: Pass<'i>,
+    PassInput<'i, P>: Input<'i, Token = u8>,
+{
+    match reason {
+        ErrorReason::Incomplete(requirement) => {
+            pass.with_input_error_incomplete(bytes_required(requirement))
+        }
+        ErrorReason::Custom(custom) => pass.with_input_error_custom(custom.clone()),
+        ErrorReason::Failure(reason) => {
+            let (err, pass) = byte_error(reason, pass);
+            (err.cut(), pass)
+        }
+        ErrorReason::Unexpected(unexpected) => {
+            let unexpected = Unexpected {
+                unexpected: match pass.input().iter().next() {
+                    Some(byte) => TokenTag::Token(byte),
+                    None => TokenTag::Tag(&[]),
+                },
+                expecting: byte_hint(&unexpected.expecting),
+            };
+            pass.with_input_error_unexpected(unexpected)
+        }
+    }
+}
+
+/// Errors of bit passes that [`bits_with_error`] can convert to errors of
+/// the byte pass around them.
+pub trait IntoByteError<'i>: Error<'i, Context = BitPassContext<'i>> {
+    /// Converts the error to one on the byte pass the bits were parsed from,
+    /// given that pass as it was before parsing the bits.
+    fn into_byte_error<P>(self, pass: P) -> (PassError<'i, P>, P)
+    where
+        P: Pass<'i>,
+        PassInput<'i, P>: Input<'i, Token = u8>;
+}
+
+/// Errors are positioned at the byte the bits failed within, with unexpected
+/// bits reported as that byte.
+impl<'i> IntoByteError<'i> for BitError<'i> {
+    fn into_byte_error<P>(self, pass: P) -> (PassError<'i, P>, P)
+    where
+        P: Pass<'i>,
+        PassInput<'i, P>: Input<'i, Token = u8>,
+    {
+        let input = pass.input();
+        let byte = self.position().offset() / 8;
+        let pass = match input.clone().split_at::<PassInputError<'i, P>>(byte) {
+            Ok((_, at)) => pass.commit(at),
+            Err(_) => pass,
+        };
+        let (mut err, pass) = byte_error(self.reason(), pass);
+        for context in self.contexts() {
+            err = err.push_context(context);
+        }
+        (err, pass.commit(input))
+    }
+}
+
+/// Runs a parser over the bits of a byte input, then resumes parsing bytes.
+///
+/// Parsing resumes from the byte boundary after the last bit consumed, so
+/// any bits left in that byte are skipped. Errors are converted to bytes,
+/// at the byte the bits failed within, with unexpected bits reported as
+/// that byte.
+pub fn bits<'i, P, F, O>(sub: F) -> impl Fn(P) -> PassResult<'i, P, O>
+where
+    P: Pass<'i>,
+    PassInput<'i, P>: Input<'i, Token = u8> + AsSlice<'i>,
+    F: Fn(BitPass<'i, BitError<'i>>) -> PassResult<'i, BitPass<'i, BitError<'i>>, O>,
+{
+    bits_with_error(sub)
+}
+
+/// Runs a parser over the bits of a byte input with its own error type.
+///
+/// See [`bits`].
+pub fn bits_with_error<'i, P, F, O, E>(sub: F) -> impl Fn(P) -> PassResult<'i, P, O>
+where
+    P: Pass<'i>,
+    PassInput<'i, P>: Input<'i, Token = u8> + AsSlice<'i>,
+    F: Fn(BitPass<'i, E>) -> PassResult<'i, BitPass<'i, E>, O>,
+    E: IntoByteError<'i>,
+{
+    move |pass: P| {
+        let input = pass.input();
+        let bytes = SliceInput::from(input.as_slice());
+        let bit_input = if input.is_complete() {
+            BitInput::complete(bytes)
+        } else {
+            BitInput::partial(bytes)
+        };
+        match sub(BitPass::new(bit_input)) {
+            Ok((out, bit_pass)) => {
+                let consumed = bit_pass.position().offset().div_ceil(8);
+                let ((_, rest), pass) = pass.with_input_result(input.split_at(consumed))?;
+                Ok((out, pass.commit(rest)))
+            }
+            Err((err, _)) => Err(err.into_byte_error(pass)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::byte::*;
+    use crate::pass::{Position, SlicePass, SlicePassContext};
+
+    use assert_matches::assert_matches;
+
+    type TestContext = SlicePassContext<'static, u8>;
+    type TestError = VerboseError<'static, TestContext>;
+    type TestPass = SlicePass<'static, u8, TestError>;
+    type TestBitPass = BitPass<'static, BitError<'static>>;
+
+    fn test_pass(input: &'static [u8]) -> TestPass {
+        TestPass::from(input)
+    }
+
+    fn test_bit_pass(input: &'static [u8]) -> TestBitPass {
+        TestBitPass::from(input)
+    }
+
+    #[test]
+    fn test_take_bits() {
+        let fields = seq((take_bits::<_, u8>(3), take_bits::<_, u16>(13)));
+
+        assert_matches!(
+            fields(test_bit_pass(&[0b0100_0001, 0x23])),
+            Ok(((0b010, 0x0123), pass_out)) => {
+                assert_eq!(pass_out.position(), Position::new(16));
+            }
+        );
+    }
+
+    #[test]
+    fn test_bit_and_align() {
+        let parser = seq((bit, bit, align, take_bits::<_, u8>(8)));
+
+        assert_matches!(
+            parser(test_bit_pass(&[0b0100_0000, 0xab])),
+            Ok(((false, true, (), 0xab), _))
+        );
+        assert_matches!(preceded(align, bit)(test_bit_pass(&[0x80])), Ok((true, _)));
+    }
+
+    #[test]
+    fn test_bits_ipv4_header() {
+        let version_ihl = bits(pair(take_bits::<_, u8>(4), take_bits::<_, u8>(4)));
+        let flags = bits(seq((bit, bit, bit)));
+        let parser = seq((version_ihl, u8, be_u16, be_u16, flags, u8));
+
+        assert_matches!(
+            parser(test_pass(&[0x45, 0x00, 0x00, 0x54, 0xab, 0xcd, 0x40, 0x00, 0x40])),
+            Ok((((4, 5), 0x00, 0x54, 0xabcd, (false, true, false), 0x00), pass_out)) => {
+                assert_eq!(pass_out, test_pass(&[0x40]));
+            }
+        );
+    }
+
+    #[test]
+    fn test_bits_errors() {
+        assert_matches!(
+            bits(take_bits::<_, u16>(12))(test_pass(&[0xab])),
+            Err((err, _)) => {
+                assert_eq!(err.reason(), &ErrorReason::Incomplete(Requirement::Exact(1)));
+            }
+        );
+        assert_matches!(
+            bits(preceded(take_bits::<_, u16>(9), hint(token(true), "flag")))(test_pass(&[0xff, 0x80])),
+            Err((err, _)) => {
+                assert_eq!(err.reason(), &ErrorReason::Unexpected(Unexpected {
+                    unexpected: TokenTag::Token(0x80),
+                    expecting: ExpectedHint::Description("flag"),
+                }));
+                assert_eq!(err.position(), Position::new(1));
+            }
+        );
+    }
+
+    #[test]
+    fn test_bits_nested_failure() {
+        let nested = |pass: TestBitPass| {
+            let unexpected = ErrorReason::Unexpected(Unexpected {
+                unexpected: TokenTag::Token(true),
+                expecting: ExpectedHint::None,
+            });
+            let failure =
+                ErrorReason::Failure(Box::new(ErrorReason::Failure(Box::new(unexpected))));
+            Err::<((), _), _>(pass.with_input_error(failure))
+        };
+
+        assert_matches!(
+            bits(nested)(test_pass(&[0xff])),
+            Err((err, _)) => {
+                assert!(err.is_failure());
+                assert_eq!(err.reason().uncut(), &ErrorReason::Unexpected(Unexpected {
+                    unexpected: TokenTag::Token(0xff),
+                    expecting: ExpectedHint::None,
+                }));
+            }
+        );
+    }
+
+    #[test]
+    fn test_bits_error_position() {
+        let flags = bits(hint(
+            hint(preceded(take_bits::<_, u16>(9), token(true)), "flag"),
+            "flags",
+        ));
+
+        assert_matches!(
+            preceded(u8, flags)(test_pass(&[0x00, 0xff, 0x00])),
+            Err((err, pass_out)) => {
+                assert_eq!(err.reason(), &ErrorReason::Unexpected(Unexpected {
+                    unexpected: TokenTag::Token(0x00),
+                    expecting: ExpectedHint::Description("flag"),
+                }));
+                assert_eq!(err.position(), Position::new(2));
+                assert_eq!(err.contexts(), &["flags"]);
+                assert_eq!(pass_out.position(), Position::new(1));
+            }
+        );
+    }
+}
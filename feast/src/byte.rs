@@ -1 +1,401 @@
+use crate::core::*;
+use crate::input::{ExpectedHint, Input, Requirement, TokenTag, Unexpected};
+use crate::pass::{Pass, PassInput, PassInputError, PassResult};
 
+/// The byte order of a multi-byte value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Endianness {
+    /// Most significant byte first.
+    Big,
+    /// Least significant byte first.
+    Little,
+    /// The byte order of the target platform.
+    Native,
+}
+
+/// Consumes exactly `N` bytes into an array.
+fn take_bytes<'i, P, const N: usize>(pass: P) -> PassResult<'i, P, [u8; N]>
+where
+    P: Pass<'i>,
+    PassInput<'i, P>: Input<'i, Token = u8>,
+{
+    let (section, pass) = take_input(N)(pass)?;
+    let mut bytes = [0u8; N];
+    for (byte, token) in bytes.iter_mut().zip(section.iter()) {
+        *byte = token;
+    }
+    Ok((bytes, pass))
+}
+
+/// Consumes a byte as a `u8`.
+pub fn u8<'i, P>(pass: P) -> PassResult<'i, P, u8>
+where
+    P: Pass<'i>,
+    PassInput<'i, P>: Input<'i, Token = u8>,
+{
+    take_token()(pass)
+}
+
+/// Consumes a byte as an `i8`.
+pub fn i8<'i, P>(pass: P) -> PassResult<'i, P, i8>
+where
+    P: Pass<'i>,
+    PassInput<'i, P>: Input<'i, Token = u8>,
+{
+    map(take_token(), |byte: u8| byte as i8)(pass)
+}
+
+macro_rules! impl_number_parsers {
+    ($($ty:ident => $be:ident, $le:ident, $ne:ident;)*) => {
+        $(
+            #[doc = concat!("Parses a big-endian `", stringify!($ty), "`.")]
+            pub fn $be<'i, P>(pass: P) -> PassResult<'i, P, $ty>
+            where
+                P: Pass<'i>,
+                PassInput<'i, P>: Input<'i, Token = u8>,
+            {
+                map(take_bytes, $ty::from_be_bytes)(pass)
+            }
+
+            #[doc = concat!("Parses a little-endian `", stringify!($ty), "`.")]
+            pub fn $le<'i, P>(pass: P) -> PassResult<'i, P, $ty>
+            where
+                P: Pass<'i>,
+                PassInput<'i, P>: Input<'i, Token = u8>,
+            {
+                map(take_bytes, $ty::from_le_bytes)(pass)
+            }
+
+            #[doc = concat!("Parses a native-endian `", stringify!($ty), "`.")]
+            pub fn $ne<'i, P>(pass: P) -> PassResult<'i, P, $ty>
+            where
+                P: Pass<'i>,
+                PassInput<'i, P>: Input<'i, Token = u8>,
+            {
+                map(take_bytes, $ty::from_ne_bytes)(pass)
+            }
+
+            #[doc = concat!("Parses a `", stringify!($ty), "` with an endianness selected at runtime.")]
+            pub fn $ty<'i, P>(endianness: Endianness) -> impl Fn(P) -> PassResult<'i, P, $ty>
+            where
+                P: Pass<'i>,
+                PassInput<'i, P>: Input<'i, Token = u8>,
+            {
+                move |pass: P| match endianness {
+                    Endianness::Big => $be(pass),
+                    Endianness::Little => $le(pass),
+                    Endianness::Native => $ne(pass),
+                }
+            }
+        )*
+    };
+}
+
+impl_number_parsers! {
+    u16 => be_u16, le_u16, ne_u16;
+    u32 => be_u32, le_u32, ne_u32;
+    u64 => be_u64, le_u64, ne_u64;
+    u128 => be_u128, le_u128, ne_u128;
+    i16 => be_i16, le_i16, ne_i16;
+    i32 => be_i32, le_i32, ne_i32;
+    i64 => be_i64, le_i64, ne_i64;
+    i128 => be_i128, le_i128, ne_i128;
+    f32 => be_f32, le_f32, ne_f32;
+    f64 => be_f64, le_f64, ne_f64;
+}
+
+/// Decodes a LEB128 value of at most `bits` wide.
+///
+/// Signed values are returned sign extended to the width of a `u128`.
+fn leb128<'i, P>(
+    pass: P,
+    bits: u32,
+    signed: bool,
+    description: &'static str,
+) -> PassResult<'i, P, u128>
+where
+    P: Pass<'i>,
+    PassInput<'i, P>: Input<'i, Token = u8>,
+{
+    let max_len = bits.div_ceil(7) as usize;
+    let mut input = pass.input();
+    let mut value = 0u128;
+    let mut shift = 0;
+    for i in 0..max_len {
+        let byte = match input.split_first::<PassInputError<'i, P>>() {
+            Ok((byte, rest)) => {
+                input = rest;
+                byte
+            }
+            Err(_) => {
+                let remaining = max_len - i;
+                let requirement = if remaining == 1 {
+                    Requirement::Exact(1)
+                } else {
+                    Requirement::Between(1, remaining)
+                };
+                return Err(pass.with_input_error_incomplete(requirement));
+            }
+        };
+        let payload = byte & 0x7f;
+        if i == max_len - 1 {
+            // The final byte may only carry the bits that fit in the value,
+            // and for signed values the remainder must be a sign extension.
+            let used = bits - shift;
+            let excess = if signed {
+                let sign = (payload >> (used - 1)) & 1;
+                let expected = if sign == 1 { 0x7f >> (used - 1) } else { 0 };
+                (payload >> (used - 1)) != expected
+            } else {
+                payload >> used != 0
+            };
+            if byte & 0x80 != 0 || excess {
+                return Err(pass.with_input_error_unexpected(Unexpected {
+                    unexpected: TokenTag::Token(byte),
+                    expecting: ExpectedHint::Description(description),
+                }));
+            }
+        }
+        value |= u128::from(payload) << shift;
+        shift += 7;
+        if byte & 0x80 == 0 {
+            if signed && shift < 128 && payload & 0x40 != 0 {
+                value |= !0 << shift;
+            }
+            return Ok((value, pass.commit(input)));
+        }
+    }
+    unreachable!("final leb128 byte always terminates")
+}
+
+macro_rules! impl_leb128_parsers {
+    ($($name:ident => $ty:ident, $kind:literal, $signed:expr;)*) => {
+        $(
+            #[doc = concat!(
+                "Parses a ",
+                stringify!($ty),
+                " encoded as ",
+                $kind,
+                " LEB128.\n\nOverlong or overflowing encodings are unexpected."
+            )]
+            pub fn $name<'i, P>(pass: P) -> PassResult<'i, P, $ty>
+            where
+                P: Pass<'i>,
+                PassInput<'i, P>: Input<'i, Token = u8>,
+            {
+                let (value, pass) = leb128(
+                    pass,
+                    $ty::BITS,
+                    $signed,
+                    concat!("valid leb128 ", stringify!($ty)),
+                )?;
+                Ok((value as $ty, pass))
+            }
+        )*
+    };
+}
+
+impl_leb128_parsers! {
+    uleb128_u16 => u16, "unsigned", false;
+    uleb128_u32 => u32, "unsigned", false;
+    uleb128_u64 => u64, "unsigned", false;
+    uleb128_u128 => u128, "unsigned", false;
+    sleb128_i16 => i16, "signed", true;
+    sleb128_i32 => i32, "signed", true;
+    sleb128_i64 => i64, "signed", true;
+    sleb128_i128 => i128, "signed", true;
+}
+
+/// Parses a protobuf style varint, which is an unsigned LEB128 `u64`.
+pub fn varint<'i, P>(pass: P) -> PassResult<'i, P, u64>
+where
+    P: Pass<'i>,
+    PassInput<'i, P>: Input<'i, Token = u8>,
+{
+    uleb128_u64(pass)
+}
+
+/// Parses a zigzag encoded `i32` varint.
+pub fn zigzag_i32<'i, P>(pass: P) -> PassResult<'i, P, i32>
+where
+    P: Pass<'i>,
+    PassInput<'i, P>: Input<'i, Token = u8>,
+{
+    map(uleb128_u32, |n| (n >> 1) as i32 ^ -((n & 1) as i32))(pass)
+}
+
+/// Parses a zigzag encoded `i64` varint.
+pub fn zigzag_i64<'i, P>(pass: P) -> PassResult<'i, P, i64>
+where
+    P: Pass<'i>,
+    PassInput<'i, P>: Input<'i, Token = u8>,
+{
+    map(uleb128_u64, |n| (n >> 1) as i64 ^ -((n & 1) as i64))(pass)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::{ErrorReason, Requirement};
+    use crate::pass::{Error, SlicePass, SlicePassContext, VerboseError};
+
+    use assert_matches::assert_matches;
+
+    type TestContext = SlicePassContext<'static, u8>;
+    type TestError = VerboseError<'static, TestContext>;
+    type TestPass = SlicePass<'static, u8, TestError>;
+
+    fn test_pass(input: &'static [u8]) -> TestPass {
+        TestPass::from(input)
+    }
+
+    fn empty_pass() -> TestPass {
+        test_pass(b"")
+    }
+
+    #[test]
+    fn test_integers() {
+        let pass = test_pass(&[0x12, 0x34, 0x56, 0x78]);
+
+        assert_matches!(be_u32(pass.clone()), Ok((0x1234_5678, pass_out)) => {
+            assert_eq!(pass_out, empty_pass());
+        });
+        assert_matches!(le_u32(pass.clone()), Ok((0x7856_3412, _)));
+        assert_matches!(be_u16(pass.clone()), Ok((0x1234, pass_out)) => {
+            assert_eq!(pass_out, test_pass(&[0x56, 0x78]));
+        });
+        assert_matches!(be_i16(test_pass(&[0xff, 0xfe])), Ok((-2, _)));
+        assert_matches!(le_i16(test_pass(&[0xfe, 0xff])), Ok((-2, _)));
+        assert_matches!(i8(test_pass(&[0x80])), Ok((-128, _)));
+    }
+
+    #[test]
+    fn test_floats() {
+        let pass = test_pass(&[0x3f, 0xf8, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00]);
+
+        assert_matches!(be_f64(pass), Ok((value, _)) => {
+            assert_eq!(value, 1.5);
+        });
+        assert_matches!(le_f32(test_pass(&[0x00, 0x00, 0xc0, 0x3f])), Ok((value, _)) => {
+            assert_eq!(value, 1.5);
+        });
+    }
+
+    #[test]
+    fn test_runtime_endianness() {
+        let pass = test_pass(&[0x01, 0x02]);
+
+        assert_matches!(u16(Endianness::Big)(pass.clone()), Ok((0x0102, _)));
+        assert_matches!(u16(Endianness::Little)(pass.clone()), Ok((0x0201, _)));
+        assert_matches!(
+            u16(Endianness::Native)(pass),
+            Ok((value, _)) => assert_eq!(value, u16::from_ne_bytes([0x01, 0x02]))
+        );
+    }
+
+    #[test]
+    fn test_incomplete() {
+        assert_matches!(
+            be_u64(test_pass(&[0x01, 0x02, 0x03])),
+            Err((err, pass_out)) => {
+                assert_eq!(err, TestError::from_input(pass_out.context(), ErrorReason::Incomplete(Requirement::Exact(5))));
+            }
+        );
+    }
+
+    #[test]
+    fn test_uleb128() {
+        assert_matches!(uleb128_u32(test_pass(&[0x00])), Ok((0, _)));
+        assert_matches!(uleb128_u32(test_pass(&[0xe5, 0x8e, 0x26, 0xff])), Ok((624_485, pass_out)) => {
+            assert_eq!(pass_out, test_pass(&[0xff]));
+        });
+        assert_matches!(
+            uleb128_u16(test_pass(&[0xff, 0xff, 0x03])),
+            Ok((u16::MAX, _))
+        );
+        assert_matches!(
+            uleb128_u64(test_pass(&[
+                0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x01
+            ])),
+            Ok((u64::MAX, _))
+        );
+        assert_matches!(varint(test_pass(&[0x96, 0x01])), Ok((150, _)));
+    }
+
+    #[test]
+    fn test_sleb128() {
+        assert_matches!(sleb128_i32(test_pass(&[0x7f])), Ok((-1, _)));
+        assert_matches!(
+            sleb128_i32(test_pass(&[0xc0, 0xbb, 0x78])),
+            Ok((-123_456, _))
+        );
+        assert_matches!(
+            sleb128_i16(test_pass(&[0x80, 0x80, 0x7e])),
+            Ok((i16::MIN, _))
+        );
+        assert_matches!(
+            sleb128_i64(test_pass(&[
+                0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x7f
+            ])),
+            Ok((i64::MIN, _))
+        );
+    }
+
+    #[test]
+    fn test_zigzag() {
+        assert_matches!(zigzag_i32(test_pass(&[0x00])), Ok((0, _)));
+        assert_matches!(zigzag_i32(test_pass(&[0x01])), Ok((-1, _)));
+        assert_matches!(zigzag_i32(test_pass(&[0x02])), Ok((1, _)));
+        assert_matches!(
+            zigzag_i64(test_pass(&[0xff, 0xff, 0xff, 0xff, 0x0f])),
+            Ok((-2_147_483_648, _))
+        );
+    }
+
+    #[test]
+    fn test_leb128_incomplete() {
+        assert_matches!(
+            uleb128_u32(test_pass(&[0x80, 0x80])),
+            Err((err, pass_out)) => {
+                assert_eq!(err, TestError::from_input(pass_out.context(), ErrorReason::Incomplete(Requirement::Between(1, 3))));
+            }
+        );
+        assert_matches!(
+            uleb128_u32(test_pass(&[0x80, 0x80, 0x80, 0x80])),
+            Err((err, pass_out)) => {
+                assert_eq!(err, TestError::from_input(pass_out.context(), ErrorReason::Incomplete(Requirement::Exact(1))));
+            }
+        );
+    }
+
+    #[test]
+    fn test_leb128_invalid() {
+        let unexpected = |byte, description| {
+            ErrorReason::Unexpected(Unexpected {
+                unexpected: TokenTag::Token(byte),
+                expecting: ExpectedHint::Description(description),
+            })
+        };
+        // Overlong, the final byte still has a continuation bit.
+        assert_matches!(
+            uleb128_u16(test_pass(&[0x80, 0x80, 0x80, 0x00])),
+            Err((err, pass_out)) => {
+                assert_eq!(err, TestError::from_input(pass_out.context(), unexpected(0x80, "valid leb128 u16")));
+            }
+        );
+        // Overflowing, the final byte has bits beyond the width.
+        assert_matches!(
+            uleb128_u16(test_pass(&[0xff, 0xff, 0x04])),
+            Err((err, pass_out)) => {
+                assert_eq!(err, TestError::from_input(pass_out.context(), unexpected(0x04, "valid leb128 u16")));
+            }
+        );
+        // Signed overflow, the final byte is not a sign extension.
+        assert_matches!(
+            sleb128_i16(test_pass(&[0x80, 0x80, 0x3e])),
+            Err((err, pass_out)) => {
+                assert_eq!(err, TestError::from_input(pass_out.context(), unexpected(0x3e, "valid leb128 i16")));
+            }
+        );
+    }
+}
@@ -0,0 +1,343 @@
+use crate::input::Capture;
+use crate::pass::{Error, Pass, PassError, PassResult, Position};
+
+/// A choice of parsers, tried in order until one succeeds.
+///
+/// Implemented for tuples of parsers up to 12 elements.
+pub trait Alt<'i, P, O>
+where
+    P: Pass<'i>,
+{
+    /// Runs each parser in order from the same point, returning the output
+    /// of the first to succeed.
+    fn parse_alt(&self, pass: P) -> PassResult<'i, P, O>;
+}
+
+/// Tracks the failure that made the furthest progress through the input.
+struct Failure<E> {
+    err: E,
+    position: Position,
+    /// The alternative needs more input before the others can be tried, or
+    /// failed past a cut so the others must not be.
+    decisive: bool,
+}
+
+impl<'i, E> Failure<E>
+where
+    E: Error<'i>,
+{
+    fn new(err: E, position: Position) -> Self {
+        Self {
+            err,
+            position,
+            decisive: false,
+        }
+    }
+
+    fn or(self, other: Self) -> Self {
+        if other.position > self.position {
+            other
+        } else if other.position == self.position {
+            Self::new(self.err.merge(other.err), self.position)
+        } else {
+            self
+        }
+    }
+}
+
+type AltResult<'i, P, O> = Result<(O, P), (Failure<PassError<'i, P>>, P)>;
+
+fn try_alternative<'i, P, F, O>(
+    sub: &F,
+    pass: P,
+    failure: Option<Failure<PassError<'i, P>>>,
+) -> AltResult<'i, P, O>
+where
+    P: Pass<'i>,
+    F: Fn(P) -> PassResult<'i, P, O>,
+{
+    let start = pass.input();
+    match sub(pass) {
+        Ok(ok) => Ok(ok),
+        Err((err, pass)) => {
+            let mut this = Failure::new(err, pass.position());
+            if this.err.is_failure() || (!this.err.is_fatal() && !start.is_complete()) {
+                this.decisive = true;
+                return Err((this, pass));
+            }
+            let failure = match failure {
+                Some(failure) => failure.or(this),
+                None => this,
+            };
+            Err((failure, pass.commit(start)))
+        }
+    }
+}
+
+macro_rules! impl_alt {
+    ($first:ident $(, $parser:ident)*) => {
+        impl<'i, P, O, $first, $($parser),*> Alt<'i, P, O> for ($first, $($parser),*)
+        where
+            P: Pass<'i>,
+            $first: Fn(P) -> PassResult<'i, P, O>,
+            $($parser: Fn(P) -> PassResult<'i, P, O>),*
+        {
+            #[allow(non_snake_case)]
+            fn parse_alt(&self, pass: P) -> PassResult<'i, P, O> {
+                let ($first, $($parser),*) = self;
+                let (failure, pass) = match try_alternative($first, pass, None) {
+                    Ok(ok) => return Ok(ok),
+                    Err(failed) => failed,
+                };
+                $(
+                    if failure.decisive {
+                        return Err((failure.err, pass));
+                    }
+                    let (failure, pass) = match try_alternative($parser, pass, Some(failure)) {
+                        Ok(ok) => return Ok(ok),
+                        Err(failed) => failed,
+                    };
+                )*
+                Err((failure.err, pass))
+            }
+        }
+    };
+}
+
+impl_alt!(A, B);
+impl_alt!(A, B, C);
+impl_alt!(A, B, C, D);
+impl_alt!(A, B, C, D, E);
+impl_alt!(A, B, C, D, E, F);
+impl_alt!(A, B, C, D, E, F, G);
+impl_alt!(A, B, C, D, E, F, G, H);
+impl_alt!(A, B, C, D, E, F, G, H, I);
+impl_alt!(A, B, C, D, E, F, G, H, I, J);
+impl_alt!(A, B, C, D, E, F, G, H, I, J, K);
+impl_alt!(A, B, C, D, E, F, G, H, I, J, K, L);
+
+/// Tries a tuple of parsers in order, returning the output of the first to
+/// succeed.
+///
+/// If every alternative fails, the error from the alternative that made the
+/// furthest progress is returned. Errors from alternatives that failed at the
+/// same point are merged, so that what was expected lists every alternative.
+///
+/// If an alternative fails because the input is incomplete and more may
+/// still arrive, or fails past a [`cut`], that error is returned without
+/// trying the rest.
+pub fn alt<'i, P, A, O>(alternatives: A) -> impl Fn(P) -> PassResult<'i, P, O>
+where
+    P: Pass<'i>,
+    A: Alt<'i, P, O>,
+{
+    move |pass: P| alternatives.parse_alt(pass)
+}
+
+/// Tries a parser, returning `None` with the pass restored if it fails.
+///
+/// If the parser fails because the input is incomplete and more may still
+/// arrive, or fails past a [`cut`], that error is returned instead.
+pub fn opt<'i, P, F, O>(sub: F) -> impl Fn(P) -> PassResult<'i, P, Option<O>>
+where
+    P: Pass<'i>,
+    F: Fn(P) -> PassResult<'i, P, O>,
+{
+    move |pass: P| {
+        let input = pass.input();
+        match sub(pass) {
+            Ok((out, pass)) => Ok((Some(out), pass)),
+            Err((err, pass)) if err.is_failure() || (!err.is_fatal() && !input.is_complete()) => {
+                Err((err, pass))
+            }
+            Err((_, pass)) => Ok((None, pass.commit(input))),
+        }
+    }
+}
+
+/// Commits to a parser, turning its unexpected errors into failures.
+///
+/// A failure is not backtracked from, so [`alt`] tries no further
+/// alternatives, [`opt`] does not return `None` and repetition does not stop
+/// cleanly. Cutting once the start of a construct is recognised reports the
+/// error within it, rather than that it was not recognised at all, and
+/// avoids reparsing it as each alternative.
+///
+/// Errors from incomplete input are kept, as more input may still succeed.
+pub fn cut<'i, P, F, O>(sub: F) -> impl Fn(P) -> PassResult<'i, P, O>
+where
+    P: Pass<'i>,
+    F: Fn(P) -> PassResult<'i, P, O>,
+{
+    move |pass: P| match sub(pass) {
+        Err((err, pass)) => Err((err.cut(), pass)),
+        ok => ok,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ascii::*;
+    use crate::core::*;
+    use crate::input::{ErrorReason, ExpectedHint, Requirement, TokenTag, Unexpected};
+    use crate::pass::{SlicePass, SlicePassContext, VerboseError};
+
+    use assert_matches::assert_matches;
+    use std::cell::Cell;
+
+    type TestContext = SlicePassContext<'static, u8>;
+    type TestError = VerboseError<'static, TestContext>;
+    type TestPass = SlicePass<'static, u8, TestError>;
+
+    fn test_pass(input: &'static [u8]) -> TestPass {
+        TestPass::from(input)
+    }
+
+    fn empty_pass() -> TestPass {
+        test_pass(b"")
+    }
+
+    #[test]
+    fn test_opt() {
+        assert_matches!(
+            opt(ascii_digit)(test_pass(b"1a")),
+            Ok((Some(b'1'), pass_out)) => assert_eq!(pass_out, test_pass(b"a"))
+        );
+        assert_matches!(
+            opt(pair(ascii_digit, ascii_digit))(test_pass(b"1a")),
+            Ok((None, pass_out)) => assert_eq!(pass_out, test_pass(b"1a"))
+        );
+    }
+
+    #[test]
+    fn test_alt_first_success() {
+        let method = alt((tag(b"GET"), tag(b"POST"), tag(b"PUT")));
+
+        assert_matches!(
+            method(test_pass(b"PUT")),
+            Ok((out, pass_out)) => {
+                assert_eq!(out, (&b"PUT"[..]).into());
+                assert_eq!(pass_out, empty_pass());
+            }
+        );
+    }
+
+    #[test]
+    fn test_alt_merged_error() {
+        let method = alt((tag(b"GET"), tag(b"POST"), tag(b"PUT")));
+
+        assert_matches!(
+            method(test_pass(b"DELETE")),
+            Err((err, pass_out)) => {
+                assert_eq!(pass_out, test_pass(b"DELETE"));
+                assert_eq!(err, TestError::from_input(pass_out.context(), ErrorReason::Unexpected(Unexpected {
+                    unexpected: TokenTag::Token(b'D'),
+                    expecting: ExpectedHint::OneOf(vec![
+                        ExpectedHint::Tag(b"GET"),
+                        ExpectedHint::Tag(b"POST"),
+                        ExpectedHint::Tag(b"PUT"),
+                    ]),
+                })));
+            }
+        );
+    }
+
+    #[test]
+    fn test_alt_furthest_error() {
+        let parser = alt((
+            map(pair(ascii_digit, ascii_digit), |_| ()),
+            map(ascii_alphabetic, |_| ()),
+        ));
+
+        assert_matches!(
+            parser(test_pass(b"1a")),
+            Err((err, pass_out)) => {
+                assert_eq!(pass_out, test_pass(b"1a"));
+                assert_eq!(err.position(), Position::new(1));
+                assert_eq!(err.reason(), &ErrorReason::Unexpected(Unexpected {
+                    unexpected: TokenTag::Token(b'a'),
+                    expecting: ExpectedHint::Description("valid ascii decimal digit"),
+                }));
+            }
+        );
+    }
+
+    #[test]
+    fn test_alt_incomplete() {
+        let method = alt((tag(b"GET"), tag(b"POST")));
+
+        assert_matches!(
+            method(test_pass(b"PO")),
+            Err((err, pass_out)) => {
+                assert_eq!(err, TestError::from_input(pass_out.context(), ErrorReason::Incomplete(Requirement::Between(1, 2))));
+            }
+        );
+    }
+
+    #[test]
+    fn test_cut_stops_alt() {
+        let tried = Cell::new(0);
+        let fallback = |pass| {
+            tried.set(tried.get() + 1);
+            ascii_alphabetic(pass)
+        };
+        let item = alt((
+            delimited(token(b'['), cut(ascii_digit), token(b']')),
+            fallback,
+        ));
+
+        assert_matches!(item(test_pass(b"[1]")), Ok((b'1', _)));
+        assert_matches!(
+            item(test_pass(b"[a]")),
+            Err((err, _)) => {
+                assert!(err.is_failure());
+                assert_eq!(err.position(), Position::new(1));
+                assert_eq!(err.reason(), &ErrorReason::Failure(Box::new(ErrorReason::Unexpected(Unexpected {
+                    unexpected: TokenTag::Token(b'a'),
+                    expecting: ExpectedHint::Description("valid ascii decimal digit"),
+                }))));
+            }
+        );
+        assert_eq!(tried.get(), 0);
+        assert_matches!(item(test_pass(b"a")), Ok((b'a', _)));
+        assert_eq!(tried.get(), 1);
+    }
+
+    #[test]
+    fn test_cut_stops_repetition() {
+        let digits = || many0(preceded(token(b','), ascii_digit));
+        let cut_digits = many0(preceded(token(b','), cut(ascii_digit)));
+
+        assert_matches!(
+            digits()(test_pass(b",1,2,a;")),
+            Ok((digits, pass_out)) => {
+                assert_eq!(digits, vec![b'1', b'2']);
+                assert_eq!(pass_out, test_pass(b",a;"));
+            }
+        );
+        assert_matches!(
+            cut_digits(test_pass(b",1,2,a;")),
+            Err((err, _)) => {
+                assert!(err.is_failure());
+                assert_eq!(err.position(), Position::new(5));
+            }
+        );
+        assert_matches!(
+            opt(preceded(token(b'-'), cut(ascii_digit)))(test_pass(b"-a;")),
+            Err((err, _)) => assert!(err.is_failure())
+        );
+        assert_matches!(
+            separated_list0(token(b','), preceded(token(b'#'), cut(ascii_digit)))(test_pass(b"#1,#a;")),
+            Err((err, _)) => assert_eq!(err.position(), Position::new(4))
+        );
+    }
+
+    #[test]
+    fn test_or() {
+        assert_matches!(
+            or(ascii_digit, ascii_alphabetic)(test_pass(b"a")),
+            Ok((b'a', _))
+        );
+    }
+}
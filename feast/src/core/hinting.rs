@@ -1,14 +1,16 @@
-use crate::pass::{Pass, PassResult};
+use crate::pass::{Error, Pass, PassResult};
 
-pub fn hint<'i, P, F, O>(sub: F, _description: &'static str) -> impl Fn(P) -> PassResult<'i, P, O>
+/// Describes what a parser expects, or what it is parsing.
+///
+/// If the parser fails without describing what it expected, the description
+/// is used for that, otherwise it is added as context to the error.
+pub fn hint<'i, P, F, O>(sub: F, description: &'static str) -> impl Fn(P) -> PassResult<'i, P, O>
 where
     P: Pass<'i>,
     F: Fn(P) -> PassResult<'i, P, O>,
 {
-    move |pass: P| {
-        match sub(pass) {
-            Err((err, pass)) => Err((err, pass)), // TODO: Wrap with hint description
-            ok => ok,
-        }
+    move |pass: P| match sub(pass) {
+        Err((err, pass)) => Err((err.hint(description), pass)),
+        ok => ok,
     }
 }
@@ -1,5 +1,120 @@
-use crate::input::{Input, InputMarker, Requirement};
-use crate::pass::{Pass, PassResult, PassSection, PassToken};
+use crate::input::{Capture, Input, Requirement, Token, TokenPredicate, TokenTag, Unexpected};
+use crate::pass::{Context, Pass, PassInput, PassInputError, PassResult, PassSection, PassToken};
+
+/// Where a search through input stopped.
+enum Scan {
+    /// The search stopped this many tokens into the input.
+    Found(usize),
+    /// The input ran out with this many tokens ruled out, and at least
+    /// `needed` more are required to know where the search stops.
+    Exhausted { scanned: usize, needed: usize },
+}
+
+/// Runs a search over the input of a pass, resuming from where a previous
+/// run over a shorter input ran out.
+///
+/// The search is given the input and how many tokens to skip, having been
+/// ruled out already.
+fn resume_scan<'i, P, S>(pass: &P, search: S) -> Scan
+where
+    P: Pass<'i>,
+    S: Fn(PassInput<'i, P>, usize) -> Scan,
+{
+    let input = pass.input();
+    let position = pass.position();
+    let memo = pass.context().scan_memo();
+    let (id, skip) = memo.map_or((0, 0), |memo| memo.begin(position));
+    let complete = input.is_complete();
+    let scan = search(input, skip);
+    if let (Some(memo), Scan::Exhausted { scanned, .. }, false) = (memo, &scan, complete) {
+        memo.remember(id, position, *scanned);
+    }
+    scan
+}
+
+/// Splits off the tokens already ruled out, or none if there are fewer tokens
+/// than that.
+fn skip_scanned<'i, P>(input: PassInput<'i, P>, skip: usize) -> (usize, PassInput<'i, P>)
+where
+    P: Pass<'i>,
+{
+    match input.clone().split_at::<PassInputError<'i, P>>(skip) {
+        Ok((_, rest)) => (skip, rest),
+        Err(_) => (0, input),
+    }
+}
+
+/// Scans for the first token to stop at.
+fn scan_tokens<'i, P, S>(input: PassInput<'i, P>, skip: usize, stop: &S) -> Scan
+where
+    P: Pass<'i>,
+    S: TokenPredicate<'i, PassToken<'i, P>> + ?Sized,
+{
+    let (mut scanned, rest) = skip_scanned::<P>(input, skip);
+    if let Some(tokens) = rest.as_contiguous() {
+        return match stop.find_in(tokens) {
+            Some(idx) => Scan::Found(scanned + idx),
+            None => Scan::Exhausted {
+                scanned: scanned + tokens.len(),
+                needed: 1,
+            },
+        };
+    }
+    for token in rest.iter() {
+        if stop.matches(&token) {
+            return Scan::Found(scanned);
+        }
+        scanned += 1;
+    }
+    Scan::Exhausted { scanned, needed: 1 }
+}
+
+/// Scans for the first occurrence of a tag.
+///
+/// A tag cut short by the end of input is not ruled out, so needs the rest
+/// of its tokens.
+fn scan_tag<'i, P, T>(input: PassInput<'i, P>, skip: usize, tag: &[T]) -> Scan
+where
+    P: Pass<'i>,
+    T: Token + 'i,
+    PassInput<'i, P>: Input<'i, Token = T>,
+{
+    let (mut scanned, mut rest) = skip_scanned::<P>(input, skip);
+    if let Some(tokens) = rest.as_contiguous() {
+        if let Some(idx) = T::find_tag(tokens, tag) {
+            return Scan::Found(scanned + idx);
+        }
+        let tail = tokens.len().saturating_sub(tag.len() - 1);
+        let partial = (tail..tokens.len()).find(|&idx| tag.starts_with(&tokens[idx..]));
+        let idx = partial.unwrap_or(tokens.len());
+        return Scan::Exhausted {
+            scanned: scanned + idx,
+            needed: tag.len() - (tokens.len() - idx),
+        };
+    }
+    loop {
+        let mut tokens = rest.iter();
+        let mut matched = 0;
+        loop {
+            match (tag.get(matched), tokens.next()) {
+                (None, _) => return Scan::Found(scanned),
+                (Some(_), None) => {
+                    return Scan::Exhausted {
+                        scanned,
+                        needed: tag.len() - matched,
+                    }
+                }
+                (Some(expected), Some(token)) if token == *expected => matched += 1,
+                _ => break,
+            }
+        }
+        rest = match rest.split_first::<PassInputError<'i, P>>() {
+            Ok((_, rest)) => rest,
+            Err(_) => return Scan::Exhausted { scanned, needed: 1 },
+        };
+        scanned += 1;
+    }
+}
 
 pub fn take_input<'i, P>(n: usize) -> impl Fn(P) -> PassResult<'i, P, PassSection<'i, P>>
 where
@@ -12,25 +127,309 @@ where
     }
 }
 
+/// Consumes tokens up to and including the first that matches.
+///
+/// If no token matches, at least one more is required. Runs over more of the
+/// same input resume from where the last ran out, and byte predicates search
+/// contiguous input a word at a time.
 pub fn take_input_until<'i, P, F>(pred: F) -> impl Fn(P) -> PassResult<'i, P, PassSection<'i, P>>
 where
     P: Pass<'i>,
-    F: Fn(&PassToken<'i, P>) -> bool,
+    F: TokenPredicate<'i, PassToken<'i, P>>,
+{
+    move |pass: P| match resume_scan(&pass, |input, skip| scan_tokens::<P, _>(input, skip, &pred)) {
+        Scan::Found(len) => take_input(len + 1)(pass),
+        Scan::Exhausted { needed, .. } => {
+            Err(pass.with_input_error_incomplete(Requirement::AtLeast(needed)))
+        }
+    }
+}
+
+/// Consumes tokens before the first to stop at, or all of a complete input.
+fn take_before<'i, P, S>(pass: P, stop: &S) -> PassResult<'i, P, PassSection<'i, P>>
+where
+    P: Pass<'i>,
+    S: TokenPredicate<'i, PassToken<'i, P>> + ?Sized,
+{
+    let complete = pass.input().is_complete();
+    match resume_scan(&pass, |input, skip| scan_tokens::<P, _>(input, skip, stop)) {
+        Scan::Found(len) => take_input(len)(pass),
+        Scan::Exhausted { scanned, .. } if complete => take_input(scanned)(pass),
+        Scan::Exhausted { needed, .. } => {
+            Err(pass.with_input_error_incomplete(Requirement::AtLeast(needed)))
+        }
+    }
+}
+
+/// Consumes tokens while they match, which may be none.
+///
+/// A complete input is consumed entirely if every token matches. Otherwise,
+/// reaching the end requires at least one more token, and runs over more of
+/// the same input resume from where the last ran out.
+pub fn take_while<'i, P, F>(pred: F) -> impl Fn(P) -> PassResult<'i, P, PassSection<'i, P>>
+where
+    P: Pass<'i>,
+    F: TokenPredicate<'i, PassToken<'i, P>>,
+{
+    move |pass: P| take_before(pass, &|token: &PassToken<'i, P>| !pred.matches(token))
+}
+
+/// Consumes tokens while they match, requiring at least one.
+///
+/// If the first token does not match, it is unexpected.
+pub fn take_while1<'i, P, F>(pred: F) -> impl Fn(P) -> PassResult<'i, P, PassSection<'i, P>>
+where
+    P: Pass<'i>,
+    F: TokenPredicate<'i, PassToken<'i, P>>,
 {
     move |pass: P| {
         let input = pass.input();
-        let mut marker = input.iter();
-        loop {
-            match marker.next() {
-                Some(ref token) if pred(token) => {
-                    let mark = marker.mark();
-                    let ((taken, rest), pass) = pass.with_input_result(input.split_mark(mark))?;
-                    return Ok((taken, pass.commit(rest)));
-                }
-                Some(_) => continue,
-                None => break,
+        let (taken, pass) = take_before(pass, &|token: &PassToken<'i, P>| !pred.matches(token))?;
+        if !taken.is_empty() {
+            return Ok((taken, pass));
+        }
+        match input.iter().next() {
+            Some(token) => Err(pass.with_input_error_unexpected(Unexpected {
+                unexpected: TokenTag::Token(token),
+                expecting: pred.expecting(),
+            })),
+            None => Err(pass.with_input_error_incomplete(Requirement::Exact(1))),
+        }
+    }
+}
+
+/// Consumes tokens until one matches, which may be none.
+///
+/// The matching token is not consumed. See [`take_while`] for how the end of
+/// input is handled. Byte predicates search contiguous input a word at a
+/// time.
+pub fn take_till<'i, P, F>(pred: F) -> impl Fn(P) -> PassResult<'i, P, PassSection<'i, P>>
+where
+    P: Pass<'i>,
+    F: TokenPredicate<'i, PassToken<'i, P>>,
+{
+    move |pass: P| take_before(pass, &pred)
+}
+
+/// Consumes tokens up to the first occurrence of a tag, leaving the tag.
+///
+/// If the tag is not found, at least the rest of a tag cut short by the end
+/// of input is required. Runs over more of the same input resume from where
+/// the last ran out.
+pub fn take_until<'i, P, T>(tag: &'i [T]) -> impl Fn(P) -> PassResult<'i, P, PassSection<'i, P>>
+where
+    P: Pass<'i>,
+    T: Token,
+    PassInput<'i, P>: Input<'i, Token = T>,
+{
+    move |pass: P| match resume_scan(&pass, |input, skip| scan_tag::<P, T>(input, skip, tag)) {
+        Scan::Found(len) => take_input(len)(pass),
+        Scan::Exhausted { needed, .. } => {
+            Err(pass.with_input_error_incomplete(Requirement::AtLeast(needed)))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+
+    use super::*;
+    use crate::input::{ErrorReason, ExpectedHint, StrInput, StreamInput};
+    use crate::pass::{
+        Error, SlicePass, SlicePassContext, StrPass, StrPassContext, StreamParser,
+        StreamPassContext, VerboseError,
+    };
+
+    use assert_matches::assert_matches;
+
+    type TestContext = SlicePassContext<'static, u8>;
+    type TestError = VerboseError<'static, TestContext>;
+    type TestPass = SlicePass<'static, u8, TestError>;
+    type StreamError<'i> = VerboseError<'i, StreamPassContext<'i, u8>>;
+
+    fn test_pass(input: &'static [u8]) -> TestPass {
+        TestPass::from(input)
+    }
+
+    fn empty_pass() -> TestPass {
+        test_pass(b"")
+    }
+
+    fn is_digit(token: &u8) -> bool {
+        token.is_ascii_digit()
+    }
+
+    #[test]
+    fn test_take_while() {
+        assert_matches!(
+            take_while(is_digit)(test_pass(b"123ab")),
+            Ok((out, pass_out)) => {
+                assert_eq!(out, (&b"123"[..]).into());
+                assert_eq!(pass_out, test_pass(b"ab"));
+            }
+        );
+        assert_matches!(
+            take_while(is_digit)(test_pass(b"123")),
+            Ok((_, pass_out)) => assert_eq!(pass_out, empty_pass())
+        );
+        assert_matches!(
+            take_while(is_digit)(test_pass(b"ab")),
+            Ok((out, _)) => assert!(out.is_empty())
+        );
+    }
+
+    #[test]
+    fn test_take_while1() {
+        assert_matches!(
+            take_while1(is_digit)(test_pass(b"1a")),
+            Ok((out, _)) => assert_eq!(out, (&b"1"[..]).into())
+        );
+        assert_matches!(
+            take_while1(is_digit)(test_pass(b"a")),
+            Err((err, _)) => {
+                assert!(err.is_fatal());
+                assert_eq!(err.reason(), &ErrorReason::Unexpected(Unexpected {
+                    unexpected: TokenTag::Token(b'a'),
+                    expecting: ExpectedHint::None,
+                }));
+            }
+        );
+        assert_matches!(
+            take_while1(is_digit)(empty_pass()),
+            Err((err, _)) => {
+                assert_eq!(err.reason(), &ErrorReason::Incomplete(Requirement::Exact(1)));
+            }
+        );
+    }
+
+    #[test]
+    fn test_take_till() {
+        assert_matches!(
+            take_till(|token: &u8| *token == b';')(test_pass(b"ab;c")),
+            Ok((out, pass_out)) => {
+                assert_eq!(out, (&b"ab"[..]).into());
+                assert_eq!(pass_out, test_pass(b";c"));
+            }
+        );
+    }
+
+    #[test]
+    fn test_take_till_byte_sets() {
+        let text = b"key = value; next\n";
+
+        assert_matches!(
+            take_till(b';')(test_pass(text)),
+            Ok((out, _)) => assert_eq!(out, (&b"key = value"[..]).into())
+        );
+        assert_matches!(
+            take_till([b'\n', b'='])(test_pass(text)),
+            Ok((out, _)) => assert_eq!(out, (&b"key "[..]).into())
+        );
+        assert_matches!(
+            take_input_until([b'#', b'z', b'\n'])(test_pass(text)),
+            Ok((out, pass_out)) => {
+                assert_eq!(out, (&text[..]).into());
+                assert_eq!(pass_out, empty_pass());
+            }
+        );
+    }
+
+    #[test]
+    fn test_take_until() {
+        assert_matches!(
+            take_until(b"-->")(test_pass(b"a->b-->c")),
+            Ok((out, pass_out)) => {
+                assert_eq!(out, (&b"a->b"[..]).into());
+                assert_eq!(pass_out, test_pass(b"-->c"));
+            }
+        );
+        assert_matches!(
+            take_until(b"-->")(test_pass(b"ab--")),
+            Err((err, _)) => {
+                assert_eq!(err.reason(), &ErrorReason::Incomplete(Requirement::AtLeast(1)));
+            }
+        );
+        assert_matches!(
+            take_until(b"-->")(test_pass(b"ab")),
+            Err((err, _)) => {
+                assert_eq!(err.reason(), &ErrorReason::Incomplete(Requirement::AtLeast(3)));
+            }
+        );
+    }
+
+    #[test]
+    fn test_take_until_str() {
+        type StrError = VerboseError<'static, StrPassContext<'static>>;
+        let pass = StrPass::<StrError>::from("a→b→→c");
+
+        assert_matches!(
+            take_until(&['→', '→'])(pass),
+            Ok((out, pass_out)) => {
+                assert_eq!(out, StrInput::from("a→b"));
+                assert_eq!(pass_out.input(), StrInput::from("→→c"));
+            }
+        );
+        assert_matches!(
+            take_until(&['→', 'x'])(StrPass::<StrError>::from("ab→")),
+            Err((err, _)) => {
+                assert_eq!(err.reason(), &ErrorReason::Incomplete(Requirement::AtLeast(1)));
+            }
+        );
+    }
+
+    #[test]
+    fn test_take_while_partial() {
+        let partial =
+            crate::pass::StreamPass::<u8, StreamError<'_>>::new(StreamInput::partial(b"123"), 0);
+
+        assert_matches!(
+            take_while(is_digit)(partial),
+            Err((err, _)) => {
+                assert_eq!(err.reason(), &ErrorReason::Incomplete(Requirement::AtLeast(1)));
+            }
+        );
+    }
+
+    #[test]
+    fn test_take_until_resumes() {
+        let compared = Cell::new(0);
+        let digit = |token: &u8| {
+            compared.set(compared.get() + 1);
+            token.is_ascii_digit()
+        };
+        let mut stream = StreamParser::new();
+
+        for chunk in [&b"ab"[..], b"cd", b"ef", b"gh", b"1"] {
+            stream.feed(chunk);
+            let found = stream.parse::<_, _, StreamError<'_>>(take_input_until(&digit));
+            if chunk == b"1" {
+                assert_matches!(found, Ok(Some(out)) => assert_eq!(out.tokens(), b"abcdefgh1"));
+            } else {
+                assert_matches!(found, Ok(None));
+                assert_eq!(stream.requirement(), Some(&Requirement::AtLeast(1)));
             }
         }
-        Err(pass.with_input_error_incomplete(Requirement::Unknown))
+        // Each token is compared once, however many chunks it arrived across.
+        assert_eq!(compared.get(), 9);
+    }
+
+    #[test]
+    fn test_take_until_tag_resumes() {
+        let mut stream = StreamParser::new();
+
+        stream.feed(b"hello\r");
+        assert_matches!(
+            stream.parse::<_, _, StreamError<'_>>(take_until(b"\r\n")),
+            Ok(None)
+        );
+        assert_eq!(stream.requirement(), Some(&Requirement::AtLeast(1)));
+
+        stream.feed(b"\nrest");
+        assert_matches!(
+            stream.parse::<_, _, StreamError<'_>>(take_until(b"\r\n")),
+            Ok(Some(out)) => assert_eq!(out.tokens(), b"hello")
+        );
+        assert_eq!(stream.buffered(), b"\r\nrest");
     }
 }
@@ -0,0 +1,219 @@
+use std::convert::TryInto;
+
+use crate::core::{fold_many_m_n, take_input};
+use crate::input::{ExpectedHint, Input, TokenTag, Unexpected};
+use crate::pass::{Error, Pass, PassInput, PassResult, PassSection, Position};
+
+/// Converts a parsed length, saturating if it does not fit in a `usize`.
+///
+/// A saturated length can never be satisfied, so requires more input than
+/// any buffer can hold.
+fn to_len<N>(n: N) -> usize
+where
+    N: TryInto<usize>,
+{
+    n.try_into().unwrap_or(usize::MAX)
+}
+
+/// Parses a length, then consumes that many tokens.
+pub fn length_data<'i, P, L, N>(len: L) -> impl Fn(P) -> PassResult<'i, P, PassSection<'i, P>>
+where
+    P: Pass<'i>,
+    L: Fn(P) -> PassResult<'i, P, N>,
+    N: TryInto<usize>,
+{
+    move |pass: P| {
+        let (n, pass) = len(pass)?;
+        take_input(to_len(n))(pass)
+    }
+}
+
+/// Parses a length, then runs a parser over exactly that many tokens.
+///
+/// The value must be consumed entirely, otherwise the first token left is
+/// unexpected. A value cut short by its length is unexpected rather than
+/// incomplete, as more input cannot extend it, and is reported where the
+/// value failed. Errors are returned with the pass from before the value.
+pub fn length_value<'i, P, L, N, F, O>(len: L, value: F) -> impl Fn(P) -> PassResult<'i, P, O>
+where
+    P: Pass<'i>,
+    PassInput<'i, P>: Input<'i, Section = PassInput<'i, P>>,
+    L: Fn(P) -> PassResult<'i, P, N>,
+    N: TryInto<usize>,
+    F: Fn(P) -> PassResult<'i, P, O>,
+{
+    move |pass: P| {
+        let (n, pass) = len(pass)?;
+        let n = to_len(n);
+        let input = pass.input();
+        let start = pass.position();
+        let ((section, rest), pass) = pass.with_input_result(input.clone().split_at(n))?;
+        // The section is a prefix of the input, so its position is set
+        // rather than worked out from what it leaves.
+        let (out, pass) = match value(pass.commit_at(section, start)) {
+            Ok(ok) => ok,
+            Err((err, pass)) if !err.is_fatal() => {
+                let (err, pass) = pass.with_input_error_unexpected(Unexpected {
+                    unexpected: TokenTag::Tag(&[]),
+                    expecting: ExpectedHint::Description("value within its length"),
+                });
+                return Err((err, pass.commit_at(input, start)));
+            }
+            Err((err, pass)) => return Err((err, pass.commit_at(input, start))),
+        };
+        let left = pass.input();
+        if let Some(token) = left.iter().next() {
+            let (err, pass) = pass.with_input_error_unexpected(Unexpected {
+                unexpected: TokenTag::Token(token),
+                expecting: ExpectedHint::Description("end of value"),
+            });
+            return Err((err, pass.commit_at(input, start)));
+        }
+        let end = Position::new(start.offset() + n);
+        Ok((out, pass.commit_at(rest, end)))
+    }
+}
+
+/// Parses a count, then runs a parser that many times.
+pub fn length_count<'i, P, L, N, F, O>(len: L, item: F) -> impl Fn(P) -> PassResult<'i, P, Vec<O>>
+where
+    P: Pass<'i>,
+    L: Fn(P) -> PassResult<'i, P, N>,
+    N: TryInto<usize>,
+    F: Fn(P) -> PassResult<'i, P, O>,
+{
+    move |pass: P| {
+        let (n, pass) = len(pass)?;
+        let n = to_len(n);
+        // The count is untrusted, so the items are not allocated up front.
+        let push = |mut items: Vec<O>, item: O| {
+            items.push(item);
+            items
+        };
+        fold_many_m_n(n, n, &item, Vec::new, push)(pass)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ascii::*;
+    use crate::byte::*;
+    use crate::core::*;
+    use crate::input::{ErrorReason, Requirement};
+    use crate::pass::{Position, SlicePass, SlicePassContext, VerboseError};
+
+    use assert_matches::assert_matches;
+
+    type TestContext = SlicePassContext<'static, u8>;
+    type TestError = VerboseError<'static, TestContext>;
+    type TestPass = SlicePass<'static, u8, TestError>;
+
+    fn test_pass(input: &'static [u8]) -> TestPass {
+        TestPass::from(input)
+    }
+
+    fn empty_pass() -> TestPass {
+        test_pass(b"")
+    }
+
+    #[test]
+    fn test_length_data() {
+        assert_matches!(
+            length_data(u8)(test_pass(b"\x03abcd")),
+            Ok((out, pass_out)) => {
+                assert_eq!(out, (&b"abc"[..]).into());
+                assert_eq!(pass_out, test_pass(b"d"));
+            }
+        );
+        assert_matches!(
+            length_data(be_u16)(test_pass(b"\x00\x05ab")),
+            Err((err, _)) => {
+                assert_eq!(err.reason(), &ErrorReason::Incomplete(Requirement::Exact(3)));
+            }
+        );
+        assert_matches!(
+            length_data(be_u16)(test_pass(b"\x00")),
+            Err((err, _)) => {
+                assert_eq!(err.reason(), &ErrorReason::Incomplete(Requirement::Exact(1)));
+            }
+        );
+    }
+
+    #[test]
+    fn test_length_value() {
+        let tlv = pair(u8, length_value(u8, many0(parse_ascii_digit)));
+
+        assert_matches!(
+            tlv(test_pass(b"\x01\x0212x")),
+            Ok(((1, digits), pass_out)) => {
+                assert_eq!(digits, vec![1, 2]);
+                assert_eq!(pass_out, test_pass(b"x"));
+            }
+        );
+        assert_matches!(
+            tlv(test_pass(b"\x01\x021a")),
+            Err((err, _)) => {
+                assert_eq!(err.reason(), &ErrorReason::Unexpected(Unexpected {
+                    unexpected: TokenTag::Token(b'a'),
+                    expecting: ExpectedHint::Description("end of value"),
+                }));
+            }
+        );
+    }
+
+    #[test]
+    fn test_length_value_positions() {
+        let value = length_value(u8, preceded(u8, ascii_digit));
+
+        assert_matches!(
+            value(test_pass(b"\x02\x001\xff")),
+            Ok((b'1', pass_out)) => assert_eq!(pass_out.position(), Position::new(3))
+        );
+        assert_matches!(
+            value(test_pass(b"\x02\x00a\xff\xff")),
+            Err((err, pass_out)) => {
+                assert_eq!(err.position(), Position::new(2));
+                assert_eq!(pass_out.position(), Position::new(1));
+            }
+        );
+        assert_matches!(
+            length_value(u8, u8)(test_pass(b"\x02\x00a\xff")),
+            Err((err, _)) => assert_eq!(err.position(), Position::new(2))
+        );
+    }
+
+    #[test]
+    fn test_length_value_truncated() {
+        assert_matches!(
+            length_value(u8, be_u32)(test_pass(b"\x02\x00\x01\x02\x03")),
+            Err((err, pass_out)) => {
+                assert!(err.is_fatal());
+                assert_eq!(err.reason(), &ErrorReason::Unexpected(Unexpected {
+                    unexpected: TokenTag::Tag(&[]),
+                    expecting: ExpectedHint::Description("value within its length"),
+                }));
+                assert_eq!(err.position(), Position::new(1));
+                assert_eq!(err.to_string(), "expected value within its length");
+                assert_eq!(pass_out, test_pass(b"\x00\x01\x02\x03"));
+            }
+        );
+    }
+
+    #[test]
+    fn test_length_count() {
+        assert_matches!(
+            length_count(u8, be_u16)(test_pass(b"\x02\x00\x01\x00\x02")),
+            Ok((items, pass_out)) => {
+                assert_eq!(items, vec![1, 2]);
+                assert_eq!(pass_out, empty_pass());
+            }
+        );
+        assert_matches!(
+            length_count(u8, be_u16)(test_pass(b"\x02\x00\x01\x00")),
+            Err((err, _)) => {
+                assert_eq!(err.reason(), &ErrorReason::Incomplete(Requirement::Exact(1)));
+            }
+        );
+    }
+}
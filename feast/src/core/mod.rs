@@ -1,12 +1,30 @@
+mod alternative;
 mod hinting;
 mod input;
+mod length;
+mod parser;
+mod recovery;
+mod recursive;
+mod repetition;
+mod sequence;
 mod token;
 
-use crate::input::{Capture, ExpectedHint, Input, Requirement, Token, TokenTag, Unexpected};
+use std::error;
+
+use crate::input::{
+    Capture, CustomError, ExpectedHint, Input, Requirement, Token, TokenTag, Unexpected,
+};
 use crate::pass::{Pass, PassInput, PassResult, PassSection};
 
+pub use self::alternative::*;
 pub use self::hinting::*;
 pub use self::input::*;
+pub use self::length::*;
+pub use self::parser::*;
+pub use self::recovery::*;
+pub use self::recursive::*;
+pub use self::repetition::*;
+pub use self::sequence::*;
 pub use self::token::*;
 
 pub fn tag<'i, P, T>(tag: &'i [T]) -> impl Fn(P) -> PassResult<'i, P, PassSection<'i, P>>
@@ -19,15 +37,15 @@ where
         let tag_len = tag.len();
         let input = pass.input();
         let ((input_tag, rest), pass) = pass.with_input_result(input.split_at(tag_len))?;
-        for i in 0..tag_len {
-            if tag[i] == input_tag[i] {
-                continue;
-            } else {
-                return Err(pass.with_input_error_unexpected(Unexpected {
-                    unexpected: TokenTag::Token(input_tag[i].clone()),
-                    expecting: ExpectedHint::Tag(tag),
-                }));
-            }
+        let mismatch = input_tag
+            .iter()
+            .zip(tag)
+            .find(|(token, expected)| token != *expected);
+        if let Some((token, _)) = mismatch {
+            return Err(pass.with_input_error_unexpected(Unexpected {
+                unexpected: TokenTag::Token(token),
+                expecting: ExpectedHint::Tag(tag),
+            }));
         }
         Ok((input_tag, pass.commit(rest)))
     }
@@ -42,24 +60,14 @@ where
     take_token_if(move |token: &T| start <= *token && *token <= end)
 }
 
+/// Tries `a`, then `b` if it fails. See [`alt`] for how errors are merged.
 pub fn or<'i, P, A, B, O>(a: A, b: B) -> impl Fn(P) -> PassResult<'i, P, O>
 where
     P: Pass<'i>,
     A: Fn(P) -> PassResult<'i, P, O>,
     B: Fn(P) -> PassResult<'i, P, O>,
 {
-    move |pass: P| {
-        match a(pass) {
-            Err((_err_a, pass)) => match b(pass) {
-                Err((err_b, pass)) => {
-                    // TODO: Better or error
-                    Err((err_b, pass))
-                }
-                ok => ok,
-            },
-            ok => ok,
-        }
-    }
+    alt((a, b))
 }
 
 pub fn peek<'i, P, F, O>(sub: F) -> impl Fn(P) -> PassResult<'i, P, O>
@@ -100,6 +108,43 @@ where
     }
 }
 
+/// Maps the output of a parser with a function that may fail.
+///
+/// If the mapping fails, its error is returned as a custom error at where
+/// the parser started.
+pub fn map_res<'i, P, F, FO, M, O, E>(sub: F, mapper: M) -> impl Fn(P) -> PassResult<'i, P, O>
+where
+    P: Pass<'i>,
+    F: Fn(P) -> PassResult<'i, P, FO>,
+    M: Fn(FO) -> Result<O, E>,
+    E: error::Error + Send + Sync + 'static,
+{
+    move |pass: P| {
+        let input = pass.input();
+        let (val, pass) = sub(pass)?;
+        match mapper(val) {
+            Ok(out) => Ok((out, pass)),
+            Err(err) => Err(pass
+                .commit(input)
+                .with_input_error_custom(CustomError::new(err))),
+        }
+    }
+}
+
+/// Checks the output of a parser.
+///
+/// If the check fails, its error is returned as a custom error at where the
+/// parser started.
+pub fn verify<'i, P, F, O, V, E>(sub: F, verifier: V) -> impl Fn(P) -> PassResult<'i, P, O>
+where
+    P: Pass<'i>,
+    F: Fn(P) -> PassResult<'i, P, O>,
+    V: Fn(&O) -> Result<(), E>,
+    E: error::Error + Send + Sync + 'static,
+{
+    map_res(sub, move |out| verifier(&out).map(|()| out))
+}
+
 pub fn complete<'i, P, F, C>(sub: F) -> impl Fn(P) -> PassResult<'i, P, C::Value>
 where
     P: Pass<'i>,
@@ -122,9 +167,16 @@ where
 mod tests {
     use super::*;
     use crate::ascii::*;
-    use crate::pass::{SlicePass, SlicePassContext, VerboseError};
+    use crate::input::{ByteSet, CharClass, ErrorReason, TokenClass};
+    use crate::pass::{
+        Error, LineColumn, Position, SlicePass, SlicePassContext, StrPass, StrPassContext,
+        VerboseError,
+    };
 
     use assert_matches::assert_matches;
+    use std::convert::TryFrom;
+    use std::fmt;
+    use std::num::TryFromIntError;
 
     type TestContext = SlicePassContext<'static, u8>;
     type TestError = VerboseError<'static, TestContext>;
@@ -138,6 +190,53 @@ mod tests {
         test_pass(b"")
     }
 
+    const IDENT_START: ByteSet = ByteSet::range(b'a', b'z')
+        .union(ByteSet::range(b'A', b'Z'))
+        .union(ByteSet::bytes(b"_"));
+
+    #[test]
+    fn test_take_token_if_byte_set() {
+        let ident = pair(take_token_if(&IDENT_START), take_while(&IDENT_START));
+
+        assert_matches!(
+            ident(test_pass(b"_id0")),
+            Ok(((b'_', rest), pass_out)) => {
+                assert_eq!(rest, (&b"id"[..]).into());
+                assert_eq!(pass_out, test_pass(b"0"));
+            }
+        );
+        assert_matches!(
+            ident(test_pass(b"0id")),
+            Err((err, _)) => {
+                assert_eq!(err.reason(), &ErrorReason::Unexpected(Unexpected {
+                    unexpected: TokenTag::Token(b'0'),
+                    expecting: ExpectedHint::Class(TokenClass::Bytes(&IDENT_START)),
+                }));
+                assert_eq!(err.to_string(), "expected [A-Z_a-z], found '0'");
+            }
+        );
+    }
+
+    #[test]
+    fn test_take_token_if_char_class() {
+        const GREEK: CharClass = CharClass::range('α', 'ω');
+        type StrError = VerboseError<'static, StrPassContext<'static>>;
+
+        assert_matches!(
+            take_while1(&GREEK)(StrPass::<StrError>::from("λx")),
+            Ok((out, _)) => assert_eq!(out.as_str(), "λ")
+        );
+        assert_matches!(
+            take_token_if(&GREEK)(StrPass::<StrError>::from("x")),
+            Err((err, _)) => {
+                assert_eq!(err.reason(), &ErrorReason::Unexpected(Unexpected {
+                    unexpected: TokenTag::Token('x'),
+                    expecting: ExpectedHint::Class(TokenClass::Chars(&GREEK)),
+                }));
+            }
+        );
+    }
+
     #[test]
     fn test_peek_simple() {
         let pass = test_pass(b"1");
@@ -176,4 +275,129 @@ mod tests {
             }
         );
     }
+
+    #[test]
+    fn test_hint_description() {
+        assert_matches!(
+            ascii_digit(test_pass(b":")),
+            Err((err, _)) => {
+                assert_eq!(err.reason(), &ErrorReason::Unexpected(Unexpected {
+                    unexpected: TokenTag::Token(b':'),
+                    expecting: ExpectedHint::Description("valid ascii decimal digit"),
+                }));
+                assert!(err.contexts().is_empty());
+            }
+        );
+    }
+
+    #[test]
+    fn test_hint_contexts() {
+        let version = hint(preceded(tag(b"v"), ascii_digit), "version");
+        let header = hint(preceded(tag(b"HDR "), version), "header");
+
+        assert_matches!(
+            header(test_pass(b"HDR v:")),
+            Err((err, _)) => {
+                assert_eq!(err.contexts(), &["version", "header"]);
+                assert_eq!(
+                    err.to_string(),
+                    "while parsing header > while parsing version: \
+                     expected valid ascii decimal digit, found ':'"
+                );
+            }
+        );
+    }
+
+    fn number(pass: TestPass) -> PassResult<'static, TestPass, u32> {
+        fold_many1(parse_ascii_digit, || 0u32, |n, d| n * 10 + u32::from(d))(pass)
+    }
+
+    #[test]
+    fn test_map_res_custom_error() {
+        let port = hint(preceded(token(b':'), map_res(number, u8::try_from)), "port");
+
+        assert_matches!(port(test_pass(b":80")), Ok((80, _)));
+        assert_matches!(
+            port(test_pass(b":300;")),
+            Err((err, pass_out)) => {
+                assert_eq!(pass_out, test_pass(b"300;"));
+                assert_eq!(err.position(), Position::new(1));
+                assert_eq!(err.contexts(), &["port"]);
+                let custom = err.reason().custom().unwrap();
+                assert!(custom.downcast_ref::<TryFromIntError>().is_some());
+                assert_eq!(
+                    err.to_string(),
+                    "while parsing port: out of range integral type conversion attempted"
+                );
+            }
+        );
+    }
+
+    #[derive(Debug)]
+    struct ChecksumMismatch {
+        expected: u32,
+        found: u32,
+    }
+
+    impl fmt::Display for ChecksumMismatch {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(
+                f,
+                "checksum {} does not match {}",
+                self.found, self.expected
+            )
+        }
+    }
+
+    impl error::Error for ChecksumMismatch {}
+
+    #[test]
+    fn test_verify_custom_error() {
+        let checked = verify(
+            pair(terminated(number, token(b'#')), number),
+            |&(value, checksum)| match value % 7 {
+                expected if expected == checksum => Ok(()),
+                expected => Err(ChecksumMismatch {
+                    expected,
+                    found: checksum,
+                }),
+            },
+        );
+
+        assert_matches!(checked(test_pass(b"30#2;")), Ok(((30, 2), _)));
+        assert_matches!(
+            checked(test_pass(b"30#3;")),
+            Err((err, pass_out)) => {
+                assert_eq!(pass_out, test_pass(b"30#3;"));
+                assert!(!err.is_failure());
+                assert_eq!(err.to_string(), "checksum 3 does not match 2");
+            }
+        );
+        assert_matches!(
+            cut(checked)(test_pass(b"30#3;")),
+            Err((err, _)) => {
+                assert!(err.is_failure());
+                let custom = err.reason().custom().unwrap();
+                assert_eq!(custom.downcast_ref::<ChecksumMismatch>().unwrap().expected, 2);
+            }
+        );
+    }
+
+    #[test]
+    fn test_position() {
+        let pass = test_pass(b"12\n3a");
+        let number = many0(or(ascii_digit, token(b'\n')));
+
+        assert_matches!(
+            number(pass),
+            Ok((_, pass_out)) => {
+                assert_eq!(pass_out.position(), Position::new(4));
+                assert_eq!(pass_out.context().line_column(), LineColumn { line: 2, column: 2 });
+
+                assert_matches!(ascii_digit(pass_out), Err((err, _)) => {
+                    assert_eq!(err.position(), Position::new(4));
+                });
+            }
+        );
+    }
 }
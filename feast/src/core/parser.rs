@@ -0,0 +1,397 @@
+use crate::core::{and_then, cut, hint, many0, map, opt, or, peek};
+use crate::pass::{Pass, PassResult};
+
+/// A parser, run over a pass to produce an output.
+///
+/// Implemented for every `Fn(P) -> PassResult<'i, P, O>`, so any of the
+/// combinator functions or a plain `fn` is a parser. The methods chain
+/// combinators, returning named types that can be stored and passed around.
+/// A parser built this way can be handed back to the combinator functions
+/// with [`Parser::into_fn`].
+pub trait Parser<'i, P>
+where
+    P: Pass<'i>,
+{
+    type Output;
+
+    /// Runs the parser over a pass.
+    fn parse(&self, pass: P) -> PassResult<'i, P, Self::Output>;
+
+    /// Maps the output of the parser. See [`map`].
+    fn map<M, O>(self, mapper: M) -> Map<Self, M>
+    where
+        Self: Sized,
+        M: Fn(Self::Output) -> O,
+    {
+        Map {
+            parser: self,
+            mapper,
+        }
+    }
+
+    /// Continues with the output of the parser and the pass after it. See
+    /// [`and_then`].
+    fn and_then<T, O>(self, then: T) -> AndThen<Self, T>
+    where
+        Self: Sized,
+        T: Fn((Self::Output, P)) -> PassResult<'i, P, O>,
+    {
+        AndThen { parser: self, then }
+    }
+
+    /// Tries another parser if this one fails. See [`or`].
+    fn or<B>(self, other: B) -> Or<Self, B>
+    where
+        Self: Sized,
+        B: Parser<'i, P, Output = Self::Output>,
+    {
+        Or {
+            parser: self,
+            other,
+        }
+    }
+
+    /// Makes the parser optional. See [`opt`].
+    fn opt(self) -> Opt<Self>
+    where
+        Self: Sized,
+    {
+        Opt { parser: self }
+    }
+
+    /// Repeats the parser zero or more times. See [`many0`].
+    fn many0(self) -> Many0<Self>
+    where
+        Self: Sized,
+    {
+        Many0 { parser: self }
+    }
+
+    /// Describes what the parser expects. See [`hint`].
+    fn hint(self, description: &'static str) -> Hint<Self>
+    where
+        Self: Sized,
+    {
+        Hint {
+            parser: self,
+            description,
+        }
+    }
+
+    /// Commits to the parser, so its errors are not backtracked from. See
+    /// [`cut`].
+    fn cut(self) -> Cut<Self>
+    where
+        Self: Sized,
+    {
+        Cut { parser: self }
+    }
+
+    /// Runs the parser without consuming input. See [`peek`].
+    fn peek(self) -> Peek<Self>
+    where
+        Self: Sized,
+    {
+        Peek { parser: self }
+    }
+
+    /// Boxes the parser, erasing its type.
+    fn boxed<'p>(self) -> BoxedParser<'p, 'i, P, Self::Output>
+    where
+        Self: Sized + 'p,
+    {
+        BoxedParser::new(self)
+    }
+
+    /// Converts the parser into a function, as the combinator functions take.
+    fn into_fn(self) -> impl Fn(P) -> PassResult<'i, P, Self::Output>
+    where
+        Self: Sized,
+    {
+        move |pass: P| self.parse(pass)
+    }
+}
+
+impl<'i, P, F, O> Parser<'i, P> for F
+where
+    P: Pass<'i>,
+    F: Fn(P) -> PassResult<'i, P, O>,
+{
+    type Output = O;
+
+    fn parse(&self, pass: P) -> PassResult<'i, P, O> {
+        self(pass)
+    }
+}
+
+/// A parser of dynamic type, such as one picked at runtime.
+pub struct BoxedParser<'p, 'i, P, O> {
+    parser: Box<dyn Parser<'i, P, Output = O> + 'p>,
+}
+
+impl<'p, 'i, P, O> BoxedParser<'p, 'i, P, O>
+where
+    P: Pass<'i>,
+{
+    /// Create a boxed parser.
+    pub fn new<F>(parser: F) -> Self
+    where
+        F: Parser<'i, P, Output = O> + 'p,
+    {
+        Self {
+            parser: Box::new(parser),
+        }
+    }
+}
+
+impl<'p, 'i, P, O> Parser<'i, P> for BoxedParser<'p, 'i, P, O>
+where
+    P: Pass<'i>,
+{
+    type Output = O;
+
+    fn parse(&self, pass: P) -> PassResult<'i, P, O> {
+        self.parser.parse(pass)
+    }
+}
+
+/// Runs a parser, for the combinator functions to take.
+macro_rules! run {
+    ($parser:expr) => {
+        |pass: P| $parser.parse(pass)
+    };
+}
+
+/// A parser with its output mapped, from [`Parser::map`].
+#[derive(Clone, Debug)]
+pub struct Map<F, M> {
+    parser: F,
+    mapper: M,
+}
+
+impl<'i, P, F, M, O> Parser<'i, P> for Map<F, M>
+where
+    P: Pass<'i>,
+    F: Parser<'i, P>,
+    M: Fn(F::Output) -> O,
+{
+    type Output = O;
+
+    fn parse(&self, pass: P) -> PassResult<'i, P, O> {
+        map(run!(self.parser), &self.mapper)(pass)
+    }
+}
+
+/// A parser continued by another step, from [`Parser::and_then`].
+#[derive(Clone, Debug)]
+pub struct AndThen<F, T> {
+    parser: F,
+    then: T,
+}
+
+impl<'i, P, F, T, O> Parser<'i, P> for AndThen<F, T>
+where
+    P: Pass<'i>,
+    F: Parser<'i, P>,
+    T: Fn((F::Output, P)) -> PassResult<'i, P, O>,
+{
+    type Output = O;
+
+    fn parse(&self, pass: P) -> PassResult<'i, P, O> {
+        and_then(run!(self.parser), &self.then)(pass)
+    }
+}
+
+/// A choice of two parsers, from [`Parser::or`].
+#[derive(Clone, Debug)]
+pub struct Or<A, B> {
+    parser: A,
+    other: B,
+}
+
+impl<'i, P, A, B> Parser<'i, P> for Or<A, B>
+where
+    P: Pass<'i>,
+    A: Parser<'i, P>,
+    B: Parser<'i, P, Output = A::Output>,
+{
+    type Output = A::Output;
+
+    fn parse(&self, pass: P) -> PassResult<'i, P, A::Output> {
+        or(run!(self.parser), run!(self.other))(pass)
+    }
+}
+
+/// An optional parser, from [`Parser::opt`].
+#[derive(Clone, Debug)]
+pub struct Opt<F> {
+    parser: F,
+}
+
+impl<'i, P, F> Parser<'i, P> for Opt<F>
+where
+    P: Pass<'i>,
+    F: Parser<'i, P>,
+{
+    type Output = Option<F::Output>;
+
+    fn parse(&self, pass: P) -> PassResult<'i, P, Self::Output> {
+        opt(run!(self.parser))(pass)
+    }
+}
+
+/// A repeated parser, from [`Parser::many0`].
+#[derive(Clone, Debug)]
+pub struct Many0<F> {
+    parser: F,
+}
+
+impl<'i, P, F> Parser<'i, P> for Many0<F>
+where
+    P: Pass<'i>,
+    F: Parser<'i, P>,
+{
+    type Output = Vec<F::Output>;
+
+    fn parse(&self, pass: P) -> PassResult<'i, P, Self::Output> {
+        many0(run!(self.parser))(pass)
+    }
+}
+
+/// A parser with a description, from [`Parser::hint`].
+#[derive(Clone, Debug)]
+pub struct Hint<F> {
+    parser: F,
+    description: &'static str,
+}
+
+impl<'i, P, F> Parser<'i, P> for Hint<F>
+where
+    P: Pass<'i>,
+    F: Parser<'i, P>,
+{
+    type Output = F::Output;
+
+    fn parse(&self, pass: P) -> PassResult<'i, P, F::Output> {
+        hint(run!(self.parser), self.description)(pass)
+    }
+}
+
+/// A parser that does not consume input, from [`Parser::peek`].
+#[derive(Clone, Debug)]
+pub struct Peek<F> {
+    parser: F,
+}
+
+impl<'i, P, F> Parser<'i, P> for Peek<F>
+where
+    P: Pass<'i>,
+    F: Parser<'i, P>,
+{
+    type Output = F::Output;
+
+    fn parse(&self, pass: P) -> PassResult<'i, P, F::Output> {
+        peek(run!(self.parser))(pass)
+    }
+}
+
+/// A parser committed to, from [`Parser::cut`].
+#[derive(Clone, Debug)]
+pub struct Cut<F> {
+    parser: F,
+}
+
+impl<'i, P, F> Parser<'i, P> for Cut<F>
+where
+    P: Pass<'i>,
+    F: Parser<'i, P>,
+{
+    type Output = F::Output;
+
+    fn parse(&self, pass: P) -> PassResult<'i, P, F::Output> {
+        cut(run!(self.parser))(pass)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ascii::*;
+    use crate::core::*;
+    use crate::input::{ErrorReason, ExpectedHint, TokenTag, Unexpected};
+    use crate::pass::{SlicePass, SlicePassContext, VerboseError};
+
+    use assert_matches::assert_matches;
+
+    type TestContext = SlicePassContext<'static, u8>;
+    type TestError = VerboseError<'static, TestContext>;
+    type TestPass = SlicePass<'static, u8, TestError>;
+
+    fn test_pass(input: &'static [u8]) -> TestPass {
+        TestPass::from(input)
+    }
+
+    #[test]
+    fn test_parser_chaining() {
+        let number = parse_ascii_digit
+            .many0()
+            .map(|digits| digits.into_iter().fold(0u32, |n, d| n * 10 + u32::from(d)));
+        let signed = token(b'-').opt().and_then(|(sign, pass)| {
+            let (n, pass) = number.parse(pass)?;
+            Ok((
+                if sign.is_some() {
+                    -(n as i64)
+                } else {
+                    n as i64
+                },
+                pass,
+            ))
+        });
+
+        assert_matches!(signed.parse(test_pass(b"-42;")), Ok((-42, _)));
+        assert_matches!(signed.parse(test_pass(b"7")), Ok((7, _)));
+    }
+
+    #[test]
+    fn test_parser_or_hint_peek() {
+        let sign = || token(b'+').or(token(b'-')).hint("sign");
+
+        assert_matches!(
+            sign().peek().parse(test_pass(b"-1")),
+            Ok((b'-', pass_out)) => assert_eq!(pass_out, test_pass(b"-1"))
+        );
+        assert_matches!(
+            sign().parse(test_pass(b"1")),
+            Err((err, _)) => {
+                assert_eq!(err.reason(), &ErrorReason::Unexpected(Unexpected {
+                    unexpected: TokenTag::Token(b'1'),
+                    expecting: ExpectedHint::Description("sign"),
+                }));
+            }
+        );
+    }
+
+    #[test]
+    fn test_parser_into_fn() {
+        let digits = parse_ascii_digit.many0().into_fn();
+
+        assert_matches!(
+            terminated(digits, token(b';'))(test_pass(b"12;")),
+            Ok((digits, _)) => assert_eq!(digits, vec![1, 2])
+        );
+    }
+
+    #[test]
+    fn test_boxed_parser() {
+        let parsers: Vec<BoxedParser<'_, '_, TestPass, u8>> = vec![
+            ascii_digit.boxed(),
+            ascii_alphabetic
+                .map(|ch: u8| ch.to_ascii_uppercase())
+                .boxed(),
+        ];
+
+        assert_matches!(parsers[0].parse(test_pass(b"1")), Ok((b'1', _)));
+        assert_matches!(parsers[1].parse(test_pass(b"a")), Ok((b'A', _)));
+        assert_matches!(parsers[1].parse(test_pass(b"1")), Err(_));
+    }
+}
@@ -0,0 +1,147 @@
+use crate::core::{map, opt, pair, take_input, take_till};
+use crate::input::{Capture, TokenPredicate};
+use crate::pass::{Error, Pass, PassResult, PassToken, Recover};
+
+/// Runs a parser, recovering from its errors with another.
+///
+/// If the parser fails, the recovery is run from where it started instead,
+/// typically skipping to a point parsing can resume from to produce a
+/// placeholder. If that consumes input, the error is recorded on the pass
+/// and the placeholder returned. Otherwise the error is returned, so that
+/// recovering never loops or hides the end of input.
+///
+/// Errors from incomplete input are returned, as more input may still
+/// succeed, and a recovery that is incomplete returns its own error.
+pub fn recover_with<'i, P, F, R, O>(sub: F, recovery: R) -> impl Fn(P) -> PassResult<'i, P, O>
+where
+    P: Recover<'i>,
+    F: Fn(P) -> PassResult<'i, P, O>,
+    R: Fn(P) -> PassResult<'i, P, O>,
+{
+    move |pass: P| {
+        let input = pass.input();
+        let position = pass.position();
+        let (err, pass) = match sub(pass) {
+            Ok(ok) => return Ok(ok),
+            Err((err, pass)) if !err.is_fatal() && !input.is_complete() => return Err((err, pass)),
+            Err(failed) => failed,
+        };
+        match recovery(pass.commit(input.clone())) {
+            Ok((out, pass)) if pass.position() != position => Ok((out, pass.record(err))),
+            Err((recovery_err, pass)) if !recovery_err.is_fatal() && !input.is_complete() => {
+                Err((recovery_err, pass))
+            }
+            Ok((_, pass)) | Err((_, pass)) => Err((err, pass.commit(input))),
+        }
+    }
+}
+
+/// Skips tokens up to a synchronising token, leaving it, to produce a
+/// placeholder. A complete input is skipped entirely if there is none.
+pub fn skip_until<'i, P, F, D, O>(sync: F, placeholder: D) -> impl Fn(P) -> PassResult<'i, P, O>
+where
+    P: Pass<'i>,
+    F: TokenPredicate<'i, PassToken<'i, P>>,
+    D: Fn() -> O,
+{
+    map(take_till(sync), move |_| placeholder())
+}
+
+/// Skips tokens up to and including a synchronising token, to produce a
+/// placeholder. A complete input is skipped entirely if there is none.
+pub fn skip_past<'i, P, F, D, O>(sync: F, placeholder: D) -> impl Fn(P) -> PassResult<'i, P, O>
+where
+    P: Pass<'i>,
+    F: TokenPredicate<'i, PassToken<'i, P>>,
+    D: Fn() -> O,
+{
+    map(pair(take_till(sync), opt(take_input(1))), move |_| {
+        placeholder()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ascii::*;
+    use crate::core::*;
+    use crate::input::{AsSlice, ErrorReason, ExpectedHint, TokenTag, Unexpected};
+    use crate::pass::{Position, RecoveryPass, SlicePass, SlicePassContext, VerboseError};
+
+    use assert_matches::assert_matches;
+
+    type TestContext = SlicePassContext<'static, u8>;
+    type TestError = VerboseError<'static, TestContext>;
+    type TestPass = RecoveryPass<'static, SlicePass<'static, u8, TestError>>;
+
+    fn test_pass(input: &'static [u8]) -> TestPass {
+        TestPass::new(SlicePass::from(input))
+    }
+
+    /// Parses a setting such as `a=1;`, or a placeholder for a bad one.
+    ///
+    /// Results carry the recovery pass, so are large with its diagnostics.
+    #[allow(clippy::result_large_err)]
+    fn setting(pass: TestPass) -> PassResult<'static, TestPass, Option<(u8, u8)>> {
+        let key = ascii_alphabetic;
+        let value = preceded(token(b'='), cut(ascii_digit));
+        recover_with(
+            map(terminated(pair(key, value), cut(token(b';'))), Some),
+            skip_past(b';', || None),
+        )(pass)
+    }
+
+    #[test]
+    fn test_recover_all_errors() {
+        assert_matches!(
+            many0(setting)(test_pass(b"a=1;b=x;c=3;=4;d=5")),
+            Ok((settings, pass_out)) => {
+                assert_eq!(settings, vec![
+                    Some((b'a', b'1')),
+                    None,
+                    Some((b'c', b'3')),
+                    None,
+                    None,
+                ]);
+                assert_eq!(pass_out.input().as_slice(), b"");
+                let diagnostics = pass_out.diagnostics();
+                assert_eq!(diagnostics.len(), 3);
+                assert_eq!(diagnostics[0].position(), Position::new(6));
+                assert_eq!(diagnostics[0].reason(), &ErrorReason::Failure(Box::new(ErrorReason::Unexpected(Unexpected {
+                    unexpected: TokenTag::Token(b'x'),
+                    expecting: ExpectedHint::Description("valid ascii decimal digit"),
+                }))));
+                assert_eq!(diagnostics[1].position(), Position::new(12));
+                assert_eq!(diagnostics[2].position(), Position::new(18));
+            }
+        );
+    }
+
+    #[test]
+    fn test_recover_backtracked() {
+        let confirmed = terminated(setting, token(b'!'));
+
+        assert_matches!(
+            opt(confirmed)(test_pass(b"a=x;")),
+            Ok((None, pass_out)) => {
+                assert_eq!(pass_out.input().as_slice(), b"a=x;");
+                assert!(pass_out.diagnostics().is_empty());
+            }
+        );
+    }
+
+    #[test]
+    fn test_recover_needs_progress() {
+        assert_matches!(
+            setting(test_pass(b"")),
+            Err((err, pass_out)) => {
+                assert!(!err.is_failure());
+                assert!(pass_out.diagnostics().is_empty());
+            }
+        );
+        assert_matches!(
+            skip_until(b';', || ())(test_pass(b"=4;")),
+            Ok(((), pass_out)) => assert_eq!(pass_out.input().as_slice(), b";")
+        );
+    }
+}
@@ -0,0 +1,247 @@
+use std::cell::{Cell, OnceCell};
+use std::rc::{Rc, Weak};
+
+use crate::core::{BoxedParser, Parser};
+use crate::input::{ExpectedHint, Input, TokenTag, Unexpected};
+use crate::pass::{Error, Pass, PassError, PassResult};
+
+/// The default limit on how deeply a [`recursive`] parser may nest.
+pub const DEFAULT_MAX_DEPTH: usize = 128;
+
+struct Shared<'p, 'i, P, O> {
+    parser: OnceCell<BoxedParser<'p, 'i, P, O>>,
+    depth: Cell<usize>,
+    max_depth: usize,
+}
+
+impl<'p, 'i, P, O> Shared<'p, 'i, P, O>
+where
+    P: Pass<'i>,
+{
+    /// Runs the parser a level deeper.
+    ///
+    /// One level past the limit the parser is still run, but any deeper
+    /// fails, so that whether it would have parsed anything is known. If it
+    /// does, parsing fails as if cut. If not, its error is returned as is.
+    fn parse(&self, pass: P) -> PassResult<'i, P, O> {
+        let depth = self.depth.get();
+        if depth > self.max_depth {
+            return Err(too_deep(pass));
+        }
+        let parser = self
+            .parser
+            .get()
+            .expect("recursive parser run while it is being built");
+        self.depth.set(depth + 1);
+        let _guard = DepthGuard(&self.depth);
+        if depth < self.max_depth {
+            return parser.parse(pass);
+        }
+        let input = pass.input();
+        let position = pass.position();
+        match parser.parse(pass) {
+            Ok((_, pass)) | Err((_, pass)) if pass.position() != position => {
+                let (err, pass) = too_deep(pass.commit(input));
+                Err((err.cut(), pass))
+            }
+            result => result,
+        }
+    }
+}
+
+fn too_deep<'i, P>(pass: P) -> (PassError<'i, P>, P)
+where
+    P: Pass<'i>,
+{
+    unexpected(pass, "less deeply nested input")
+}
+
+fn unexpected<'i, P>(pass: P, expecting: &'static str) -> (PassError<'i, P>, P)
+where
+    P: Pass<'i>,
+{
+    let unexpected = match pass.input().iter().next() {
+        Some(token) => TokenTag::Token(token),
+        None => TokenTag::Tag(&[]),
+    };
+    pass.with_input_error_unexpected(Unexpected {
+        unexpected,
+        expecting: ExpectedHint::Description(expecting),
+    })
+}
+
+/// Restores the depth on leaving a level, even by unwinding.
+struct DepthGuard<'a>(&'a Cell<usize>);
+
+impl<'a> Drop for DepthGuard<'a> {
+    fn drop(&mut self) {
+        self.0.set(self.0.get() - 1);
+    }
+}
+
+/// A parser that can refer to itself, from [`recursive`].
+pub struct Recursive<'p, 'i, P, O> {
+    shared: Rc<Shared<'p, 'i, P, O>>,
+}
+
+impl<'p, 'i, P, O> Parser<'i, P> for Recursive<'p, 'i, P, O>
+where
+    P: Pass<'i>,
+{
+    type Output = O;
+
+    fn parse(&self, pass: P) -> PassResult<'i, P, O> {
+        self.shared.parse(pass)
+    }
+}
+
+/// A reference to a [`Recursive`] parser from within its definition.
+///
+/// The reference does not keep the parser alive. Once the parser is
+/// dropped, parsing through the reference fails as if cut.
+pub struct RecursiveRef<'p, 'i, P, O> {
+    shared: Weak<Shared<'p, 'i, P, O>>,
+}
+
+impl<'p, 'i, P, O> Clone for RecursiveRef<'p, 'i, P, O> {
+    fn clone(&self) -> Self {
+        Self {
+            shared: self.shared.clone(),
+        }
+    }
+}
+
+impl<'p, 'i, P, O> Parser<'i, P> for RecursiveRef<'p, 'i, P, O>
+where
+    P: Pass<'i>,
+{
+    type Output = O;
+
+    fn parse(&self, pass: P) -> PassResult<'i, P, O> {
+        match self.shared.upgrade() {
+            Some(shared) => shared.parse(pass),
+            None => {
+                let (err, pass) = unexpected(pass, "recursive parser still defined");
+                Err((err.cut(), pass))
+            }
+        }
+    }
+}
+
+/// Defines a parser in terms of itself, such as for nested grammars.
+///
+/// The definition is given a reference to the parser being defined. Nesting
+/// is limited to [`DEFAULT_MAX_DEPTH`] levels, past which parsing fails as
+/// if [`cut`](crate::core::cut) rather than the stack overflowing on hostile
+/// input.
+pub fn recursive<'p, 'i, P, O, D, F>(define: D) -> Recursive<'p, 'i, P, O>
+where
+    P: Pass<'i>,
+    D: FnOnce(RecursiveRef<'p, 'i, P, O>) -> F,
+    F: Parser<'i, P, Output = O> + 'p,
+{
+    recursive_with_max_depth(DEFAULT_MAX_DEPTH, define)
+}
+
+/// Defines a parser in terms of itself, nesting at most `max_depth` levels.
+///
+/// See [`recursive`].
+pub fn recursive_with_max_depth<'p, 'i, P, O, D, F>(
+    max_depth: usize,
+    define: D,
+) -> Recursive<'p, 'i, P, O>
+where
+    P: Pass<'i>,
+    D: FnOnce(RecursiveRef<'p, 'i, P, O>) -> F,
+    F: Parser<'i, P, Output = O> + 'p,
+{
+    let shared = Rc::new(Shared {
+        parser: OnceCell::new(),
+        depth: Cell::new(0),
+        max_depth,
+    });
+    let parser = define(RecursiveRef {
+        shared: Rc::downgrade(&shared),
+    });
+    if shared.parser.set(BoxedParser::new(parser)).is_err() {
+        unreachable!("recursive parser defined twice");
+    }
+    Recursive { shared }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::*;
+    use crate::input::ErrorReason;
+    use crate::pass::{SlicePass, SlicePassContext, VerboseError};
+
+    use assert_matches::assert_matches;
+
+    type TestContext = SlicePassContext<'static, u8>;
+    type TestError = VerboseError<'static, TestContext>;
+    type TestPass = SlicePass<'static, u8, TestError>;
+
+    fn test_pass(input: &'static [u8]) -> TestPass {
+        TestPass::from(input)
+    }
+
+    /// Parses nested lists such as `[[],[[]]]`, returning the deepest nesting.
+    fn nesting(max_depth: usize) -> Recursive<'static, 'static, TestPass, usize> {
+        recursive_with_max_depth(max_depth, |list| {
+            let items = separated_list0(token(b','), list.into_fn());
+            delimited(token(b'['), items, token(b']'))
+                .map(|depths: Vec<usize>| depths.into_iter().max().unwrap_or(0) + 1)
+        })
+    }
+
+    #[test]
+    fn test_recursive_nesting() {
+        assert_matches!(nesting(8).parse(test_pass(b"[]")), Ok((1, _)));
+        assert_matches!(nesting(8).parse(test_pass(b"[[],[[]],[]]x")), Ok((3, pass_out)) => {
+            assert_eq!(pass_out, test_pass(b"x"));
+        });
+    }
+
+    #[test]
+    fn test_recursive_max_depth() {
+        assert_matches!(nesting(3).parse(test_pass(b"[[[]]]")), Ok((3, _)));
+        assert_matches!(
+            nesting(3).parse(test_pass(b"[[],[[[]]]]")),
+            Err((err, _)) => {
+                assert!(err.is_failure());
+                assert_eq!(err.position().offset(), 6);
+                assert_eq!(err.reason(), &ErrorReason::Failure(Box::new(ErrorReason::Unexpected(Unexpected {
+                    unexpected: TokenTag::Token(b'['),
+                    expecting: ExpectedHint::Description("less deeply nested input"),
+                }))));
+            }
+        );
+    }
+
+    #[test]
+    fn test_recursive_hostile_input() {
+        static HOSTILE: [u8; 100_000] = [b'['; 100_000];
+        let parser = nesting(DEFAULT_MAX_DEPTH);
+
+        assert_matches!(parser.parse(test_pass(&HOSTILE)), Err((err, _)) => {
+            assert_eq!(err.position().offset(), DEFAULT_MAX_DEPTH);
+        });
+        // The depth is restored after failing, so the parser can be reused.
+        assert_matches!(parser.parse(test_pass(b"[[]]")), Ok((2, _)));
+    }
+
+    #[test]
+    fn test_recursive_ref_dropped() {
+        let mut reference = None;
+        let parser = recursive(|list: RecursiveRef<'_, '_, TestPass, u8>| {
+            reference = Some(list);
+            token(b'x')
+        });
+        drop(parser);
+
+        assert_matches!(reference.unwrap().parse(test_pass(b"x")), Err((err, _)) => {
+            assert!(err.is_failure());
+        });
+    }
+}
@@ -0,0 +1,301 @@
+use crate::input::{Capture, ExpectedHint, Input, TokenTag, Unexpected};
+use crate::pass::{Error, Pass, PassResult};
+
+/// The most items preallocated for, as a count may come from the input.
+const MAX_PREALLOCATED: usize = 256;
+
+/// Repeats a parser between `min` and `max` times, folding each output.
+///
+/// Repetition stops cleanly, with the pass restored to before the failed
+/// attempt, on any error once `min` outputs have been folded. Before that
+/// the error is returned, as it is if the attempt failed only because the
+/// input is incomplete and more may still arrive, or failed past a cut.
+///
+/// If the parser succeeds without consuming input, the output is folded
+/// and repetition stops, so as to not loop forever. If `min` is not yet
+/// satisfied, the next token is unexpected instead.
+pub fn fold_many_m_n<'i, P, F, O, I, G, R>(
+    min: usize,
+    max: usize,
+    sub: F,
+    init: I,
+    fold: G,
+) -> impl Fn(P) -> PassResult<'i, P, R>
+where
+    P: Pass<'i>,
+    F: Fn(P) -> PassResult<'i, P, O>,
+    I: Fn() -> R,
+    G: Fn(R, O) -> R,
+{
+    move |mut pass: P| {
+        let mut acc = init();
+        let mut count = 0;
+        while count < max {
+            let input = pass.input();
+            let position = pass.position();
+            match sub(pass) {
+                Ok((out, next)) => {
+                    let progressed = next.position() != position;
+                    acc = fold(acc, out);
+                    count += 1;
+                    pass = next;
+                    if !progressed && count >= min {
+                        break;
+                    }
+                    if !progressed {
+                        let unexpected = match pass.input().iter().next() {
+                            Some(token) => TokenTag::Token(token),
+                            None => TokenTag::Tag(&[]),
+                        };
+                        return Err(pass.with_input_error_unexpected(Unexpected {
+                            unexpected,
+                            expecting: ExpectedHint::Description("repeated item consuming input"),
+                        }));
+                    }
+                }
+                Err((err, next)) => {
+                    if count < min || err.is_failure() || (!err.is_fatal() && !input.is_complete())
+                    {
+                        return Err((err, next));
+                    }
+                    pass = next.commit(input);
+                    break;
+                }
+            }
+        }
+        Ok((acc, pass))
+    }
+}
+
+/// Repeats a parser zero or more times, folding each output.
+pub fn fold_many0<'i, P, F, O, I, G, R>(
+    sub: F,
+    init: I,
+    fold: G,
+) -> impl Fn(P) -> PassResult<'i, P, R>
+where
+    P: Pass<'i>,
+    F: Fn(P) -> PassResult<'i, P, O>,
+    I: Fn() -> R,
+    G: Fn(R, O) -> R,
+{
+    fold_many_m_n(0, usize::MAX, sub, init, fold)
+}
+
+/// Repeats a parser one or more times, folding each output.
+pub fn fold_many1<'i, P, F, O, I, G, R>(
+    sub: F,
+    init: I,
+    fold: G,
+) -> impl Fn(P) -> PassResult<'i, P, R>
+where
+    P: Pass<'i>,
+    F: Fn(P) -> PassResult<'i, P, O>,
+    I: Fn() -> R,
+    G: Fn(R, O) -> R,
+{
+    fold_many_m_n(1, usize::MAX, sub, init, fold)
+}
+
+/// Repeats a parser between `min` and `max` times, collecting each output.
+pub fn many_m_n<'i, P, F, O>(
+    min: usize,
+    max: usize,
+    sub: F,
+) -> impl Fn(P) -> PassResult<'i, P, Vec<O>>
+where
+    P: Pass<'i>,
+    F: Fn(P) -> PassResult<'i, P, O>,
+{
+    fold_many_m_n(min, max, sub, Vec::new, push)
+}
+
+/// Repeats a parser zero or more times, collecting each output.
+pub fn many0<'i, P, F, O>(sub: F) -> impl Fn(P) -> PassResult<'i, P, Vec<O>>
+where
+    P: Pass<'i>,
+    F: Fn(P) -> PassResult<'i, P, O>,
+{
+    many_m_n(0, usize::MAX, sub)
+}
+
+/// Repeats a parser one or more times, collecting each output.
+pub fn many1<'i, P, F, O>(sub: F) -> impl Fn(P) -> PassResult<'i, P, Vec<O>>
+where
+    P: Pass<'i>,
+    F: Fn(P) -> PassResult<'i, P, O>,
+{
+    many_m_n(1, usize::MAX, sub)
+}
+
+/// Runs a parser exactly `n` times, collecting each output.
+pub fn count<'i, P, F, O>(sub: F, n: usize) -> impl Fn(P) -> PassResult<'i, P, Vec<O>>
+where
+    P: Pass<'i>,
+    F: Fn(P) -> PassResult<'i, P, O>,
+{
+    fold_many_m_n(
+        n,
+        n,
+        sub,
+        move || Vec::with_capacity(n.min(MAX_PREALLOCATED)),
+        push,
+    )
+}
+
+fn push<O>(mut items: Vec<O>, item: O) -> Vec<O> {
+    items.push(item);
+    items
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ascii::*;
+    use crate::core::*;
+    use crate::input::{ErrorReason, Requirement, StreamInput};
+    use crate::pass::{SlicePass, SlicePassContext, StreamPass, StreamPassContext, VerboseError};
+
+    use assert_matches::assert_matches;
+
+    type TestContext = SlicePassContext<'static, u8>;
+    type TestError = VerboseError<'static, TestContext>;
+    type TestPass = SlicePass<'static, u8, TestError>;
+    type StreamError<'i> = VerboseError<'i, StreamPassContext<'i, u8>>;
+
+    fn test_pass(input: &'static [u8]) -> TestPass {
+        TestPass::from(input)
+    }
+
+    fn empty_pass() -> TestPass {
+        test_pass(b"")
+    }
+
+    #[test]
+    fn test_many0() {
+        assert_matches!(
+            many0(parse_ascii_digit)(test_pass(b"123a")),
+            Ok((digits, pass_out)) => {
+                assert_eq!(digits, vec![1, 2, 3]);
+                assert_eq!(pass_out, test_pass(b"a"));
+            }
+        );
+
+        assert_matches!(
+            many0(parse_ascii_digit)(test_pass(b"a")),
+            Ok((digits, pass_out)) => {
+                assert!(digits.is_empty());
+                assert_eq!(pass_out, test_pass(b"a"));
+            }
+        );
+
+        assert_matches!(
+            many0(parse_ascii_digit)(empty_pass()),
+            Ok((digits, pass_out)) => {
+                assert!(digits.is_empty());
+                assert_eq!(pass_out, empty_pass());
+            }
+        );
+    }
+
+    #[test]
+    fn test_many0_partial_item() {
+        assert_matches!(
+            many0(tag(b"ab"))(test_pass(b"ababa")),
+            Ok((items, pass_out)) => {
+                assert_eq!(items.len(), 2);
+                assert_eq!(pass_out, test_pass(b"a"));
+            }
+        );
+    }
+
+    #[test]
+    fn test_many0_zero_progress() {
+        assert_matches!(
+            many0(peek(ascii_digit))(test_pass(b"12")),
+            Ok((digits, pass_out)) => {
+                assert_eq!(digits, vec![b'1']);
+                assert_eq!(pass_out, test_pass(b"12"));
+            }
+        );
+    }
+
+    #[test]
+    fn test_many0_incomplete_item() {
+        let partial = StreamPass::<u8, StreamError<'_>>::new(StreamInput::partial(b"aba"), 0);
+
+        assert_matches!(
+            many0(tag(b"ab"))(partial),
+            Err((err, _)) => {
+                assert_eq!(err.reason(), &ErrorReason::Incomplete(Requirement::Exact(1)));
+            }
+        );
+    }
+
+    #[test]
+    fn test_count_zero_progress() {
+        assert_matches!(
+            count(peek(ascii_digit), usize::MAX)(test_pass(b"12")),
+            Err((err, pass_out)) => {
+                assert_eq!(pass_out, test_pass(b"12"));
+                assert!(err.is_fatal());
+            }
+        );
+    }
+
+    #[test]
+    fn test_many1() {
+        assert_matches!(
+            many1(parse_ascii_digit)(test_pass(b"42")),
+            Ok((digits, pass_out)) => {
+                assert_eq!(digits, vec![4, 2]);
+                assert_eq!(pass_out, empty_pass());
+            }
+        );
+
+        assert_matches!(many1(parse_ascii_digit)(test_pass(b"a")), Err(_));
+    }
+
+    #[test]
+    fn test_many_m_n() {
+        let parser = many_m_n(2, 3, parse_ascii_digit);
+
+        assert_matches!(parser(test_pass(b"1")), Err(_));
+        assert_matches!(
+            parser(test_pass(b"12345")),
+            Ok((digits, pass_out)) => {
+                assert_eq!(digits, vec![1, 2, 3]);
+                assert_eq!(pass_out, test_pass(b"45"));
+            }
+        );
+    }
+
+    #[test]
+    fn test_count() {
+        assert_matches!(
+            count(parse_ascii_digit, 2)(test_pass(b"123")),
+            Ok((digits, pass_out)) => {
+                assert_eq!(digits, vec![1, 2]);
+                assert_eq!(pass_out, test_pass(b"3"));
+            }
+        );
+
+        assert_matches!(count(parse_ascii_digit, 2)(test_pass(b"1a")), Err(_));
+    }
+
+    #[test]
+    fn test_fold_many0() {
+        let number = fold_many0(
+            parse_ascii_digit,
+            || 0u32,
+            |acc, digit| acc * 10 + u32::from(digit),
+        );
+
+        assert_matches!(
+            number(test_pass(b"1024;")),
+            Ok((1024, pass_out)) => {
+                assert_eq!(pass_out, test_pass(b";"));
+            }
+        );
+    }
+}
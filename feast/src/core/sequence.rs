@@ -0,0 +1,365 @@
+use crate::input::Capture;
+use crate::pass::{Error, Pass, PassResult};
+
+/// A sequence of parsers run one after another.
+///
+/// Implemented for tuples of parsers up to 12 elements.
+pub trait Sequence<'i, P>
+where
+    P: Pass<'i>,
+{
+    /// The tuple of outputs from each parser.
+    type Output;
+
+    /// Runs each parser in order, threading the pass through.
+    fn parse_sequence(&self, pass: P) -> PassResult<'i, P, Self::Output>;
+}
+
+macro_rules! impl_sequence {
+    ($($parser:ident $out:ident),+) => {
+        impl<'i, P, $($parser, $out),+> Sequence<'i, P> for ($($parser,)+)
+        where
+            P: Pass<'i>,
+            $($parser: Fn(P) -> PassResult<'i, P, $out>),+
+        {
+            type Output = ($($out,)+);
+
+            #[allow(non_snake_case)]
+            fn parse_sequence(&self, pass: P) -> PassResult<'i, P, Self::Output> {
+                let ($($parser,)+) = self;
+                $(let ($out, pass) = $parser(pass)?;)+
+                Ok((($($out,)+), pass))
+            }
+        }
+    };
+}
+
+impl_sequence!(A OA);
+impl_sequence!(A OA, B OB);
+impl_sequence!(A OA, B OB, C OC);
+impl_sequence!(A OA, B OB, C OC, D OD);
+impl_sequence!(A OA, B OB, C OC, D OD, E OE);
+impl_sequence!(A OA, B OB, C OC, D OD, E OE, F OF);
+impl_sequence!(A OA, B OB, C OC, D OD, E OE, F OF, G OG);
+impl_sequence!(A OA, B OB, C OC, D OD, E OE, F OF, G OG, H OH);
+impl_sequence!(A OA, B OB, C OC, D OD, E OE, F OF, G OG, H OH, I OI);
+impl_sequence!(A OA, B OB, C OC, D OD, E OE, F OF, G OG, H OH, I OI, J OJ);
+impl_sequence!(A OA, B OB, C OC, D OD, E OE, F OF, G OG, H OH, I OI, J OJ, K OK);
+impl_sequence!(A OA, B OB, C OC, D OD, E OE, F OF, G OG, H OH, I OI, J OJ, K OK, L OL);
+
+/// Runs a tuple of parsers in order, returning a tuple of their outputs.
+pub fn seq<'i, P, S>(parsers: S) -> impl Fn(P) -> PassResult<'i, P, S::Output>
+where
+    P: Pass<'i>,
+    S: Sequence<'i, P>,
+{
+    move |pass: P| parsers.parse_sequence(pass)
+}
+
+/// Runs two parsers in order, returning both outputs.
+pub fn pair<'i, P, A, OA, B, OB>(a: A, b: B) -> impl Fn(P) -> PassResult<'i, P, (OA, OB)>
+where
+    P: Pass<'i>,
+    A: Fn(P) -> PassResult<'i, P, OA>,
+    B: Fn(P) -> PassResult<'i, P, OB>,
+{
+    move |pass: P| {
+        let (out_a, pass) = a(pass)?;
+        let (out_b, pass) = b(pass)?;
+        Ok(((out_a, out_b), pass))
+    }
+}
+
+/// Runs two parsers in order, returning the output of the second.
+pub fn preceded<'i, P, A, OA, B, OB>(a: A, b: B) -> impl Fn(P) -> PassResult<'i, P, OB>
+where
+    P: Pass<'i>,
+    A: Fn(P) -> PassResult<'i, P, OA>,
+    B: Fn(P) -> PassResult<'i, P, OB>,
+{
+    move |pass: P| {
+        let (_, pass) = a(pass)?;
+        b(pass)
+    }
+}
+
+/// Runs two parsers in order, returning the output of the first.
+pub fn terminated<'i, P, A, OA, B, OB>(a: A, b: B) -> impl Fn(P) -> PassResult<'i, P, OA>
+where
+    P: Pass<'i>,
+    A: Fn(P) -> PassResult<'i, P, OA>,
+    B: Fn(P) -> PassResult<'i, P, OB>,
+{
+    move |pass: P| {
+        let (out, pass) = a(pass)?;
+        let (_, pass) = b(pass)?;
+        Ok((out, pass))
+    }
+}
+
+/// Runs three parsers in order, returning the output of the middle.
+pub fn delimited<'i, P, A, OA, B, OB, C, OC>(
+    open: A,
+    sub: B,
+    close: C,
+) -> impl Fn(P) -> PassResult<'i, P, OB>
+where
+    P: Pass<'i>,
+    A: Fn(P) -> PassResult<'i, P, OA>,
+    B: Fn(P) -> PassResult<'i, P, OB>,
+    C: Fn(P) -> PassResult<'i, P, OC>,
+{
+    move |pass: P| {
+        let (_, pass) = open(pass)?;
+        let (out, pass) = sub(pass)?;
+        let (_, pass) = close(pass)?;
+        Ok((out, pass))
+    }
+}
+
+/// Parses zero or more items separated by a separator.
+///
+/// A separator not followed by an item is left unconsumed.
+pub fn separated_list0<'i, P, S, SO, F, O>(
+    sep: S,
+    item: F,
+) -> impl Fn(P) -> PassResult<'i, P, Vec<O>>
+where
+    P: Pass<'i>,
+    S: Fn(P) -> PassResult<'i, P, SO>,
+    F: Fn(P) -> PassResult<'i, P, O>,
+{
+    separated_list(0, false, sep, item)
+}
+
+/// Parses one or more items separated by a separator.
+///
+/// A separator not followed by an item is left unconsumed.
+pub fn separated_list1<'i, P, S, SO, F, O>(
+    sep: S,
+    item: F,
+) -> impl Fn(P) -> PassResult<'i, P, Vec<O>>
+where
+    P: Pass<'i>,
+    S: Fn(P) -> PassResult<'i, P, SO>,
+    F: Fn(P) -> PassResult<'i, P, O>,
+{
+    separated_list(1, false, sep, item)
+}
+
+/// Parses zero or more items separated by a separator, allowing a trailing
+/// separator.
+pub fn separated_trailing_list0<'i, P, S, SO, F, O>(
+    sep: S,
+    item: F,
+) -> impl Fn(P) -> PassResult<'i, P, Vec<O>>
+where
+    P: Pass<'i>,
+    S: Fn(P) -> PassResult<'i, P, SO>,
+    F: Fn(P) -> PassResult<'i, P, O>,
+{
+    separated_list(0, true, sep, item)
+}
+
+/// Parses one or more items separated by a separator, allowing a trailing
+/// separator.
+pub fn separated_trailing_list1<'i, P, S, SO, F, O>(
+    sep: S,
+    item: F,
+) -> impl Fn(P) -> PassResult<'i, P, Vec<O>>
+where
+    P: Pass<'i>,
+    S: Fn(P) -> PassResult<'i, P, SO>,
+    F: Fn(P) -> PassResult<'i, P, O>,
+{
+    separated_list(1, true, sep, item)
+}
+
+fn separated_list<'i, P, S, SO, F, O>(
+    min: usize,
+    trailing: bool,
+    sep: S,
+    item: F,
+) -> impl Fn(P) -> PassResult<'i, P, Vec<O>>
+where
+    P: Pass<'i>,
+    S: Fn(P) -> PassResult<'i, P, SO>,
+    F: Fn(P) -> PassResult<'i, P, O>,
+{
+    move |pass: P| {
+        let mut items = Vec::new();
+        let start = pass.input();
+        let mut pass = match item(pass) {
+            Ok((out, pass)) => {
+                items.push(out);
+                pass
+            }
+            Err((err, pass)) => {
+                if min > 0 || err.is_failure() || (!err.is_fatal() && !start.is_complete()) {
+                    return Err((err, pass));
+                }
+                return Ok((items, pass.commit(start)));
+            }
+        };
+        loop {
+            let before_sep = pass.input();
+            let position = pass.position();
+            let next = match sep(pass) {
+                Ok((_, next)) => next,
+                Err((err, next))
+                    if err.is_failure() || (!err.is_fatal() && !before_sep.is_complete()) =>
+                {
+                    return Err((err, next))
+                }
+                Err((_, next)) => return Ok((items, next.commit(before_sep))),
+            };
+            let after_sep = next.input();
+            pass = match item(next) {
+                Ok((out, next)) => {
+                    items.push(out);
+                    next
+                }
+                Err((err, next))
+                    if err.is_failure() || (!err.is_fatal() && !after_sep.is_complete()) =>
+                {
+                    return Err((err, next))
+                }
+                Err((_, next)) => {
+                    let rest = if trailing { after_sep } else { before_sep };
+                    return Ok((items, next.commit(rest)));
+                }
+            };
+            if pass.position() == position {
+                return Ok((items, pass));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ascii::*;
+    use crate::core::*;
+    use crate::input::{ErrorReason, Requirement, StreamInput};
+    use crate::pass::{SlicePass, SlicePassContext, StreamPass, StreamPassContext, VerboseError};
+
+    use assert_matches::assert_matches;
+
+    type TestContext = SlicePassContext<'static, u8>;
+    type TestError = VerboseError<'static, TestContext>;
+    type TestPass = SlicePass<'static, u8, TestError>;
+
+    fn test_pass(input: &'static [u8]) -> TestPass {
+        TestPass::from(input)
+    }
+
+    fn empty_pass() -> TestPass {
+        test_pass(b"")
+    }
+
+    #[test]
+    fn test_seq() {
+        assert_matches!(
+            seq((parse_ascii_digit, ascii_alphabetic, parse_ascii_digit))(test_pass(b"1a2")),
+            Ok(((1, b'a', 2), pass_out)) => {
+                assert_eq!(pass_out, empty_pass());
+            }
+        );
+
+        assert_matches!(
+            seq((parse_ascii_digit, parse_ascii_digit))(test_pass(b"1a")),
+            Err(_)
+        );
+    }
+
+    #[test]
+    fn test_pair_preceded_terminated() {
+        let pass = test_pass(b"1a");
+
+        assert_matches!(
+            pair(ascii_digit, ascii_alphabetic)(pass.clone()),
+            Ok(((b'1', b'a'), _))
+        );
+        assert_matches!(
+            preceded(ascii_digit, ascii_alphabetic)(pass.clone()),
+            Ok((b'a', _))
+        );
+        assert_matches!(
+            terminated(ascii_digit, ascii_alphabetic)(pass),
+            Ok((b'1', _))
+        );
+    }
+
+    #[test]
+    fn test_delimited() {
+        assert_matches!(
+            delimited(token(b'('), parse_ascii_digit, token(b')'))(test_pass(b"(7)")),
+            Ok((7, pass_out)) => {
+                assert_eq!(pass_out, empty_pass());
+            }
+        );
+    }
+
+    #[test]
+    fn test_separated_list() {
+        let list = separated_list0(token(b','), parse_ascii_digit);
+
+        assert_matches!(
+            list(test_pass(b"1,2,3;")),
+            Ok((digits, pass_out)) => {
+                assert_eq!(digits, vec![1, 2, 3]);
+                assert_eq!(pass_out, test_pass(b";"));
+            }
+        );
+        assert_matches!(
+            list(test_pass(b"1,2,")),
+            Ok((digits, pass_out)) => {
+                assert_eq!(digits, vec![1, 2]);
+                assert_eq!(pass_out, test_pass(b","));
+            }
+        );
+        assert_matches!(
+            list(test_pass(b";")),
+            Ok((digits, pass_out)) => {
+                assert!(digits.is_empty());
+                assert_eq!(pass_out, test_pass(b";"));
+            }
+        );
+        assert_matches!(
+            separated_list1(token(b','), parse_ascii_digit)(test_pass(b";")),
+            Err(_)
+        );
+    }
+
+    #[test]
+    fn test_separated_trailing_list() {
+        assert_matches!(
+            separated_trailing_list1(token(b','), parse_ascii_digit)(test_pass(b"1,2,;")),
+            Ok((digits, pass_out)) => {
+                assert_eq!(digits, vec![1, 2]);
+                assert_eq!(pass_out, test_pass(b";"));
+            }
+        );
+    }
+
+    #[test]
+    fn test_separated_list_incomplete() {
+        type StreamError<'i> = VerboseError<'i, StreamPassContext<'i, u8>>;
+
+        let list = separated_list0(token(b','), tag(b"ab"));
+
+        assert_matches!(
+            list(StreamPass::<u8, StreamError<'_>>::new(StreamInput::partial(b"a"), 0)),
+            Err((err, _)) => assert_eq!(err.reason(), &ErrorReason::Incomplete(Requirement::Exact(1)))
+        );
+        assert_matches!(
+            list(StreamPass::<u8, StreamError<'_>>::new(StreamInput::partial(b"ab,a"), 0)),
+            Err((err, _)) => assert_eq!(err.reason(), &ErrorReason::Incomplete(Requirement::Exact(1)))
+        );
+        assert_matches!(
+            list(StreamPass::<u8, StreamError<'_>>::new(StreamInput::partial(b";;"), 0)),
+            Ok((items, _)) => assert!(items.is_empty())
+        );
+    }
+}
@@ -1,4 +1,4 @@
-use crate::input::{ExpectedHint, Input, Token, TokenTag, Unexpected};
+use crate::input::{Input, Token, TokenPredicate, TokenTag, Unexpected};
 use crate::pass::{Pass, PassInput, PassResult, PassToken};
 
 pub fn token<'i, P, T>(token: T) -> impl Fn(P) -> PassResult<'i, P, T>
@@ -21,20 +21,24 @@ where
     }
 }
 
+/// Consumes a token if it matches a predicate.
+///
+/// If the token does not match, it is unexpected, expecting whatever the
+/// predicate describes.
 pub fn take_token_if<'i, P, F>(pred: F) -> impl Fn(P) -> PassResult<'i, P, PassToken<'i, P>>
 where
     P: Pass<'i>,
-    F: Fn(&PassToken<'i, P>) -> bool,
+    F: TokenPredicate<'i, PassToken<'i, P>>,
 {
     move |pass: P| {
         let input = pass.input();
         let ((token, rest), pass) = pass.with_input_result(input.split_first())?;
-        if pred(&token) {
+        if pred.matches(&token) {
             Ok((token, pass.commit(rest)))
         } else {
             Err(pass.with_input_error_unexpected(Unexpected {
                 unexpected: TokenTag::Token(token),
-                expecting: ExpectedHint::None,
+                expecting: pred.expecting(),
             }))
         }
     }
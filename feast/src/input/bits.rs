@@ -0,0 +1,232 @@
+use super::*;
+
+/// Input of the bits of a byte slice input, most significant bit first.
+///
+/// The input is a range of bits over the bytes it was created from. Marks are
+/// `(byte, bit)` pairs within those bytes, with bit `0` the most significant.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct BitInput<'i> {
+    bytes: SliceInput<'i, u8>,
+    start: usize,
+    end: usize,
+    complete: bool,
+}
+
+impl<'i> BitInput<'i> {
+    /// Create an input from the bits of bytes received so far, with more to
+    /// come.
+    pub fn partial(bytes: SliceInput<'i, u8>) -> Self {
+        Self {
+            start: 0,
+            end: bytes.0.len() * 8,
+            bytes,
+            complete: false,
+        }
+    }
+
+    /// Create an input from the bits of all the bytes.
+    pub fn complete(bytes: SliceInput<'i, u8>) -> Self {
+        Self {
+            complete: true,
+            ..Self::partial(bytes)
+        }
+    }
+
+    /// Returns the offset in bits from the start of the bytes.
+    pub fn offset(&self) -> usize {
+        self.start
+    }
+
+    /// Returns whether the input starts on a byte boundary.
+    pub fn is_aligned(&self) -> bool {
+        self.start.is_multiple_of(8)
+    }
+
+    /// Returns the whole bytes remaining from the next byte boundary.
+    pub fn aligned_bytes(&self) -> &'i [u8] {
+        &self.bytes.0[self.start.div_ceil(8).min(self.end / 8)..self.end / 8]
+    }
+
+    fn bit(&self, idx: usize) -> bool {
+        self.bytes[idx / 8] & (0x80 >> (idx % 8)) != 0
+    }
+
+    fn range(&self, start: usize, end: usize) -> Self {
+        Self {
+            start,
+            end,
+            ..*self
+        }
+    }
+}
+
+impl<'i> Input<'i> for BitInput<'i> {
+    type Mark = (usize, u8);
+    type Token = bool;
+    type Section = Self;
+    type Iterator = BitIterator<'i>;
+
+    fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+
+    fn split_first<E>(self) -> Result<(Self::Token, Self), E>
+    where
+        E: Error<'i, Token = Self::Token>,
+    {
+        if self.is_empty() {
+            Err(E::incomplete(Requirement::Exact(1)))
+        } else {
+            Ok((self.bit(self.start), self.range(self.start + 1, self.end)))
+        }
+    }
+
+    fn split_at<E>(self, mid: usize) -> Result<(Self::Section, Self), E>
+    where
+        E: Error<'i, Token = Self::Token>,
+    {
+        if mid > self.len() {
+            Err(E::incomplete(Requirement::Exact(mid - self.len())))
+        } else {
+            let mid = self.start + mid;
+            let section = Self {
+                complete: true,
+                ..self.range(self.start, mid)
+            };
+            Ok((section, self.range(mid, self.end)))
+        }
+    }
+
+    fn split_mark<E>(self, mark: Self::Mark) -> Result<(Self::Section, Self), E>
+    where
+        E: Error<'i, Token = Self::Token>,
+    {
+        let mid = mark.0 * 8 + usize::from(mark.1);
+        let complete = self.complete || mid != self.end;
+        let (mut section, rest) = self.split_at::<E>(mid.saturating_sub(self.start))?;
+        section.complete = complete;
+        Ok((section, rest))
+    }
+
+    fn iter(&self) -> Self::Iterator {
+        BitIterator { input: *self }
+    }
+}
+
+impl<'i> ExactSizeInput<'i> for BitInput<'i> {
+    /// Returns the length of the input in bits.
+    fn len(&self) -> usize {
+        self.end - self.start
+    }
+
+    fn get(&self, idx: usize) -> Option<bool> {
+        if idx < self.len() {
+            Some(self.bit(self.start + idx))
+        } else {
+            None
+        }
+    }
+}
+
+impl<'i> Capture for BitInput<'i> {
+    type Value = Self;
+
+    fn is_complete(&self) -> bool {
+        self.complete
+    }
+
+    fn resolve(&mut self) {
+        self.complete = true;
+    }
+
+    fn into_value(self) -> Self::Value {
+        self
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+/// Iterates the bits of an input, marking `(byte, bit)` positions.
+#[derive(Clone)]
+pub struct BitIterator<'i> {
+    input: BitInput<'i>,
+}
+
+impl<'i> InputMarker for BitIterator<'i> {
+    type Mark = (usize, u8);
+
+    fn mark(&self) -> Self::Mark {
+        (self.input.start / 8, (self.input.start % 8) as u8)
+    }
+}
+
+impl<'i> Iterator for BitIterator<'i> {
+    type Item = bool;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.input.is_empty() {
+            None
+        } else {
+            let next = self.input.bit(self.input.start);
+            self.input.start += 1;
+            Some(next)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type MockError = ErrorReason<'static, bool>;
+
+    fn bit_input(bytes: &'static [u8]) -> BitInput<'static> {
+        BitInput::complete(SliceInput::from(bytes))
+    }
+
+    #[test]
+    fn test_bit_input_split_first() {
+        let (bit, rest) = bit_input(&[0b1000_0000])
+            .split_first::<MockError>()
+            .unwrap();
+
+        assert!(bit);
+        assert_eq!(rest.len(), 7);
+        assert_eq!(
+            bit_input(&[]).split_first::<MockError>(),
+            Err(ErrorReason::Incomplete(Requirement::Exact(1)))
+        );
+    }
+
+    #[test]
+    fn test_bit_input_split_at() {
+        let input = bit_input(&[0b0000_0101, 0b1100_0000]);
+        let (section, rest) = input.split_at::<MockError>(10).unwrap();
+
+        assert_eq!(
+            section.iter().collect::<Vec<_>>(),
+            [false, false, false, false, false, true, false, true, true, true]
+        );
+        assert_eq!(rest.offset(), 10);
+        assert_eq!(
+            rest.split_at::<MockError>(7),
+            Err(ErrorReason::Incomplete(Requirement::Exact(1)))
+        );
+    }
+
+    #[test]
+    fn test_bit_input_marker() {
+        let input = BitInput::partial(SliceInput::from(&[0xff, 0x00][..]));
+        let mut iter = input.iter();
+
+        iter.nth(8);
+        assert_eq!(iter.mark(), (1, 1));
+
+        let (section, rest) = input.split_mark::<MockError>(iter.mark()).unwrap();
+        assert_eq!(section.len(), 9);
+        assert!(section.is_complete());
+        assert!(!rest.is_aligned());
+        assert_eq!(rest.aligned_bytes(), &[]);
+        assert_eq!(input.aligned_bytes(), &[0xff, 0x00]);
+    }
+}
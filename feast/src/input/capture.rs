@@ -51,7 +51,7 @@ impl<T> Capture for StreamCapture<T> {
     type Value = T;
 
     fn is_complete(&self) -> bool {
-        return self.complete;
+        self.complete
     }
 
     fn resolve(&mut self) {
@@ -92,9 +92,7 @@ impl<T> Capture for CompleteCapture<T> {
         true
     }
 
-    fn resolve(&mut self) {
-        ()
-    }
+    fn resolve(&mut self) {}
 
     fn into_value(self) -> Self::Value {
         self.value
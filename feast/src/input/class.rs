@@ -0,0 +1,592 @@
+use std::fmt::{self, Debug, Display, Write};
+use std::ops::{BitAnd, BitOr, Not, Sub};
+
+use super::{find_byte, find_byte2, find_byte3, ExpectedHint, TokenPredicate};
+
+/// A set of bytes, stored as a 256-bit bitmap.
+///
+/// Sets are built in `const` contexts, so are best declared as constants and
+/// used by reference, which lets failures describe what was expected:
+///
+/// ```
+/// use feast::input::ByteSet;
+///
+/// const IDENT_START: ByteSet = ByteSet::range(b'a', b'z')
+///     .union(ByteSet::range(b'A', b'Z'))
+///     .union(ByteSet::bytes(b"_"));
+///
+/// assert!(IDENT_START.contains(b'_'));
+/// assert_eq!(IDENT_START.to_string(), "[A-Z_a-z]");
+/// ```
+#[derive(Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct ByteSet {
+    bits: [u64; 4],
+}
+
+impl ByteSet {
+    /// Create a set of no bytes.
+    pub const fn new() -> Self {
+        Self { bits: [0; 4] }
+    }
+
+    /// Create a set of the bytes from `start` to `end` inclusive.
+    pub const fn range(start: u8, end: u8) -> Self {
+        let mut set = Self::new();
+        let mut byte = start as usize;
+        while byte <= end as usize {
+            set = set.with(byte as u8);
+            byte += 1;
+        }
+        set
+    }
+
+    /// Create a set of each of the bytes given.
+    pub const fn bytes(bytes: &[u8]) -> Self {
+        let mut set = Self::new();
+        let mut i = 0;
+        while i < bytes.len() {
+            set = set.with(bytes[i]);
+            i += 1;
+        }
+        set
+    }
+
+    /// Returns the set with a byte added.
+    pub const fn with(mut self, byte: u8) -> Self {
+        self.bits[byte as usize / 64] |= 1 << (byte % 64);
+        self
+    }
+
+    /// Returns whether the set contains a byte.
+    pub const fn contains(&self, byte: u8) -> bool {
+        self.bits[byte as usize / 64] & (1 << (byte % 64)) != 0
+    }
+
+    /// Returns the number of bytes in the set.
+    pub const fn len(&self) -> usize {
+        (self.bits[0].count_ones()
+            + self.bits[1].count_ones()
+            + self.bits[2].count_ones()
+            + self.bits[3].count_ones()) as usize
+    }
+
+    /// Returns whether the set has no bytes.
+    pub const fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the bytes in either set.
+    pub const fn union(self, other: Self) -> Self {
+        self.zip(other, 0)
+    }
+
+    /// Returns the bytes in both sets.
+    pub const fn intersection(self, other: Self) -> Self {
+        self.zip(other, 1)
+    }
+
+    /// Returns the bytes in this set but not the other.
+    pub const fn difference(self, other: Self) -> Self {
+        self.zip(other, 2)
+    }
+
+    /// Returns the bytes not in the set.
+    pub const fn complement(self) -> Self {
+        Self::new().zip(self, 3)
+    }
+
+    /// Combines the words of two sets, as `const fn` cannot take closures.
+    const fn zip(mut self, other: Self, op: u8) -> Self {
+        let mut i = 0;
+        while i < 4 {
+            self.bits[i] = match op {
+                0 => self.bits[i] | other.bits[i],
+                1 => self.bits[i] & other.bits[i],
+                2 => self.bits[i] & !other.bits[i],
+                _ => !other.bits[i],
+            };
+            i += 1;
+        }
+        self
+    }
+
+    /// Returns the bytes in the set, in order.
+    pub fn iter(&self) -> impl Iterator<Item = u8> + '_ {
+        (0..=u8::MAX).filter(move |&byte| self.contains(byte))
+    }
+
+    fn ranges(&self) -> impl Iterator<Item = (u32, u32)> + '_ {
+        runs(self.iter().map(u32::from))
+    }
+}
+
+impl<'a> TokenPredicate<'a, u8> for &'a ByteSet {
+    fn matches(&self, token: &u8) -> bool {
+        self.contains(*token)
+    }
+
+    /// Sets of up to three bytes search a word at a time.
+    fn find_in(&self, tokens: &[u8]) -> Option<usize> {
+        let mut bytes = self.iter();
+        match (self.len(), bytes.next(), bytes.next(), bytes.next()) {
+            (1, Some(a), _, _) => find_byte(a, tokens),
+            (2, Some(a), Some(b), _) => find_byte2(a, b, tokens),
+            (3, Some(a), Some(b), Some(c)) => find_byte3(a, b, c, tokens),
+            _ => tokens.iter().position(|&byte| self.contains(byte)),
+        }
+    }
+
+    fn expecting(&self) -> ExpectedHint<'a, u8> {
+        ExpectedHint::Class(TokenClass::Bytes(self))
+    }
+}
+
+impl BitOr for ByteSet {
+    type Output = Self;
+
+    fn bitor(self, other: Self) -> Self {
+        self.union(other)
+    }
+}
+
+impl BitAnd for ByteSet {
+    type Output = Self;
+
+    fn bitand(self, other: Self) -> Self {
+        self.intersection(other)
+    }
+}
+
+impl Sub for ByteSet {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        self.difference(other)
+    }
+}
+
+impl Not for ByteSet {
+    type Output = Self;
+
+    fn not(self) -> Self {
+        self.complement()
+    }
+}
+
+/// Sets are shown as a bracketed class, such as `[0-9a-f]`.
+impl Display for ByteSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.len() > 128 {
+            fmt_class(self.complement().ranges(), true, fmt_byte, f)
+        } else {
+            fmt_class(self.ranges(), false, fmt_byte, f)
+        }
+    }
+}
+
+impl Debug for ByteSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "ByteSet({})", self)
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+/// The most ranges a [`CharClass`] can hold.
+const CLASS_RANGES: usize = 32;
+const SURROGATE_START: u32 = 0xd800;
+const SURROGATE_END: u32 = 0xdfff;
+
+/// A class of characters, stored as sorted ranges of scalar values.
+///
+/// Like [`ByteSet`], classes are built in `const` contexts and used by
+/// reference. A class holds at most 32 disjoint ranges, and building one
+/// that needs more panics.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CharClass {
+    ranges: [(u32, u32); CLASS_RANGES],
+    len: usize,
+}
+
+impl CharClass {
+    /// Create a class of no characters.
+    pub const fn new() -> Self {
+        Self {
+            ranges: [(0, 0); CLASS_RANGES],
+            len: 0,
+        }
+    }
+
+    /// Create a class of the characters from `start` to `end` inclusive.
+    pub const fn range(start: char, end: char) -> Self {
+        Self::new().with_range(start as u32, end as u32)
+    }
+
+    /// Create a class of each of the characters in a string.
+    pub const fn chars(chars: &str) -> Self {
+        let bytes = chars.as_bytes();
+        let mut class = Self::new();
+        let mut i = 0;
+        while i < bytes.len() {
+            let (ch, len) = decode_utf8(bytes, i);
+            class = class.with_range(ch, ch);
+            i += len;
+        }
+        class
+    }
+
+    /// Returns whether the class contains a character.
+    pub const fn contains(&self, ch: char) -> bool {
+        let ch = ch as u32;
+        let mut i = 0;
+        while i < self.len {
+            if self.ranges[i].0 <= ch && ch <= self.ranges[i].1 {
+                return true;
+            }
+            i += 1;
+        }
+        false
+    }
+
+    /// Returns whether the class has no characters.
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the characters in either class.
+    pub const fn union(self, other: Self) -> Self {
+        let mut class = self;
+        let mut i = 0;
+        while i < other.len {
+            class = class.with_range(other.ranges[i].0, other.ranges[i].1);
+            i += 1;
+        }
+        class
+    }
+
+    /// Returns the characters in both classes.
+    pub const fn intersection(self, other: Self) -> Self {
+        self.complement().union(other.complement()).complement()
+    }
+
+    /// Returns the characters in this class but not the other.
+    pub const fn difference(self, other: Self) -> Self {
+        self.intersection(other.complement())
+    }
+
+    /// Returns the characters not in the class.
+    pub const fn complement(self) -> Self {
+        let mut class = Self::new();
+        let mut next = 0;
+        let mut i = 0;
+        while i < self.len {
+            if self.ranges[i].0 > next {
+                class = class.with_range(next, self.ranges[i].0 - 1);
+            }
+            next = self.ranges[i].1 + 1;
+            i += 1;
+        }
+        if next <= char::MAX as u32 {
+            class = class.with_range(next, char::MAX as u32);
+        }
+        class
+    }
+
+    /// Returns the class with a range of scalar values added, leaving out
+    /// surrogates.
+    const fn with_range(self, start: u32, end: u32) -> Self {
+        if start > end {
+            self
+        } else if start <= SURROGATE_END && end >= SURROGATE_START {
+            let mut class = self;
+            if start < SURROGATE_START {
+                class = class.insert(start, SURROGATE_START - 1);
+            }
+            if end > SURROGATE_END {
+                class = class.insert(SURROGATE_END + 1, end);
+            }
+            class
+        } else {
+            self.insert(start, end)
+        }
+    }
+
+    /// Inserts a range, merging it with any it overlaps or adjoins.
+    const fn insert(self, mut start: u32, mut end: u32) -> Self {
+        let mut class = Self::new();
+        let mut inserted = false;
+        let mut i = 0;
+        while i < self.len {
+            let (range_start, range_end) = self.ranges[i];
+            if range_end + 1 < start {
+                class = class.push(range_start, range_end);
+            } else if end + 1 < range_start {
+                if !inserted {
+                    class = class.push(start, end);
+                    inserted = true;
+                }
+                class = class.push(range_start, range_end);
+            } else {
+                if range_start < start {
+                    start = range_start;
+                }
+                if range_end > end {
+                    end = range_end;
+                }
+            }
+            i += 1;
+        }
+        if !inserted {
+            class = class.push(start, end);
+        }
+        class
+    }
+
+    const fn push(mut self, start: u32, end: u32) -> Self {
+        if self.len == CLASS_RANGES {
+            panic!("character class has too many ranges");
+        }
+        self.ranges[self.len] = (start, end);
+        self.len += 1;
+        self
+    }
+
+    fn ranges(&self) -> impl Iterator<Item = (u32, u32)> + '_ {
+        self.ranges[..self.len].iter().copied()
+    }
+}
+
+impl Default for CharClass {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a> TokenPredicate<'a, char> for &'a CharClass {
+    fn matches(&self, token: &char) -> bool {
+        self.contains(*token)
+    }
+
+    fn expecting(&self) -> ExpectedHint<'a, char> {
+        ExpectedHint::Class(TokenClass::Chars(self))
+    }
+}
+
+impl BitOr for CharClass {
+    type Output = Self;
+
+    fn bitor(self, other: Self) -> Self {
+        self.union(other)
+    }
+}
+
+impl BitAnd for CharClass {
+    type Output = Self;
+
+    fn bitand(self, other: Self) -> Self {
+        self.intersection(other)
+    }
+}
+
+impl Sub for CharClass {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        self.difference(other)
+    }
+}
+
+impl Not for CharClass {
+    type Output = Self;
+
+    fn not(self) -> Self {
+        self.complement()
+    }
+}
+
+/// Classes are shown as a bracketed class, such as `[a-zα-ω]`.
+impl Display for CharClass {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.contains('\0') && self.contains(char::MAX) {
+            fmt_class(self.complement().ranges(), true, fmt_char, f)
+        } else {
+            fmt_class(self.ranges(), false, fmt_char, f)
+        }
+    }
+}
+
+impl Debug for CharClass {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "CharClass({})", self)
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+/// A class of tokens a parser expected.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TokenClass<'a> {
+    Bytes(&'a ByteSet),
+    Chars(&'a CharClass),
+}
+
+impl<'a> Display for TokenClass<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TokenClass::Bytes(set) => Display::fmt(set, f),
+            TokenClass::Chars(class) => Display::fmt(class, f),
+        }
+    }
+}
+
+/// Groups ascending values into inclusive ranges of consecutive values.
+fn runs<I>(values: I) -> impl Iterator<Item = (u32, u32)>
+where
+    I: Iterator<Item = u32>,
+{
+    let mut values = values.peekable();
+    std::iter::from_fn(move || {
+        let start = values.next()?;
+        let mut end = start;
+        while values.peek() == Some(&(end + 1)) {
+            end = values.next()?;
+        }
+        Some((start, end))
+    })
+}
+
+/// Writes ranges as a bracketed class, with ranges of three or more members
+/// written as `start-end`.
+fn fmt_class<I, F>(
+    ranges: I,
+    negated: bool,
+    fmt_member: F,
+    f: &mut fmt::Formatter<'_>,
+) -> fmt::Result
+where
+    I: Iterator<Item = (u32, u32)>,
+    F: Fn(u32, &mut fmt::Formatter<'_>) -> fmt::Result,
+{
+    f.write_str(if negated { "[^" } else { "[" })?;
+    for (start, end) in ranges {
+        fmt_member(start, f)?;
+        if end > start + 1 {
+            f.write_char('-')?;
+        }
+        if end > start {
+            fmt_member(end, f)?;
+        }
+    }
+    f.write_char(']')
+}
+
+fn fmt_byte(byte: u32, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match byte {
+        0x09 | 0x0a | 0x0d | 0x20..=0x7e => fmt_char(byte, f),
+        _ => write!(f, "\\x{:02x}", byte),
+    }
+}
+
+fn fmt_char(ch: u32, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match std::char::from_u32(ch) {
+        Some(ch @ ('\\' | ']' | '[' | '-' | '^')) => write!(f, "\\{}", ch),
+        Some(ch) if ch.is_control() => write!(f, "{}", ch.escape_default()),
+        Some(ch) => f.write_char(ch),
+        None => write!(f, "\\u{{{:x}}}", ch),
+    }
+}
+
+/// Decodes the UTF-8 sequence starting at `i` of a valid string, returning
+/// its scalar value and length.
+const fn decode_utf8(bytes: &[u8], i: usize) -> (u32, usize) {
+    let first = bytes[i] as u32;
+    if first < 0x80 {
+        (first, 1)
+    } else if first < 0xe0 {
+        (((first & 0x1f) << 6) | (bytes[i + 1] as u32 & 0x3f), 2)
+    } else if first < 0xf0 {
+        let ch = ((first & 0x0f) << 12)
+            | ((bytes[i + 1] as u32 & 0x3f) << 6)
+            | (bytes[i + 2] as u32 & 0x3f);
+        (ch, 3)
+    } else {
+        let ch = ((first & 0x07) << 18)
+            | ((bytes[i + 1] as u32 & 0x3f) << 12)
+            | ((bytes[i + 2] as u32 & 0x3f) << 6)
+            | (bytes[i + 3] as u32 & 0x3f);
+        (ch, 4)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HEX: ByteSet = ByteSet::range(b'0', b'9').union(ByteSet::range(b'a', b'f'));
+    const GREEK: CharClass = CharClass::range('α', 'ω').union(CharClass::chars("_"));
+
+    #[test]
+    fn test_byte_set_algebra() {
+        let digits = ByteSet::range(b'0', b'9');
+
+        assert!(HEX.contains(b'7') && HEX.contains(b'c') && !HEX.contains(b'g'));
+        assert_eq!(HEX.len(), 16);
+        assert_eq!(HEX & digits, digits);
+        assert_eq!(HEX - digits, ByteSet::bytes(b"abcdef"));
+        assert_eq!((!HEX).len(), 240);
+        assert!(!(!HEX).contains(b'a'));
+        assert_eq!(!!HEX, HEX);
+        assert_eq!(ByteSet::range(b'z', b'a'), ByteSet::new());
+        assert_eq!(ByteSet::range(0, 255).len(), 256);
+    }
+
+    #[test]
+    fn test_byte_set_display() {
+        assert_eq!(HEX.to_string(), "[0-9a-f]");
+        assert_eq!(ByteSet::bytes(b"ab-]").to_string(), "[\\-\\]ab]");
+        assert_eq!(ByteSet::bytes(b"\n\t\xff").to_string(), "[\\t\\n\\xff]");
+        assert_eq!((!ByteSet::bytes(b"\"")).to_string(), "[^\"]");
+        assert_eq!(format!("{:?}", ByteSet::new()), "ByteSet([])");
+    }
+
+    #[test]
+    fn test_byte_set_find() {
+        let haystack = b"key = value; next\n";
+
+        assert_eq!((&ByteSet::bytes(b";")).find_in(haystack), Some(11));
+        assert_eq!((&ByteSet::bytes(b"\n;=")).find_in(haystack), Some(4));
+        assert_eq!((&HEX).find_in(haystack), Some(1));
+        assert_eq!((&ByteSet::new()).find_in(haystack), None);
+    }
+
+    #[test]
+    fn test_char_class_algebra() {
+        assert!(GREEK.contains('λ') && GREEK.contains('_') && !GREEK.contains('a'));
+        assert_eq!(
+            CharClass::chars("abcxz") - CharClass::chars("b"),
+            CharClass::chars("acxz")
+        );
+        assert_eq!(
+            CharClass::range('a', 'z') & CharClass::range('m', 'ω'),
+            CharClass::range('m', 'z')
+        );
+        assert!((!GREEK).contains('a') && !(!GREEK).contains('λ'));
+        assert_eq!(!!GREEK, GREEK);
+    }
+
+    #[test]
+    fn test_char_class_surrogates() {
+        let all = CharClass::new().complement();
+
+        assert!(all.contains('\u{d7ff}') && all.contains('\u{e000}'));
+        assert_eq!(all.to_string(), "[^]");
+        assert_eq!((!all).to_string(), "[]");
+    }
+
+    #[test]
+    fn test_char_class_display() {
+        assert_eq!(GREEK.to_string(), "[_α-ω]");
+        assert_eq!(CharClass::chars("ab").to_string(), "[ab]");
+        assert_eq!((!CharClass::chars("\n")).to_string(), "[^\\n]");
+    }
+}
@@ -1,6 +1,9 @@
-use super::token::{Token, TokenTag};
+use super::class::TokenClass;
+use super::token::{fmt_tokens, Token, TokenTag};
 
-use std::fmt::Debug;
+use std::error;
+use std::fmt::{self, Debug, Display};
+use std::sync::Arc;
 
 pub trait Error<'a>: Debug + PartialEq {
     type Token: Token;
@@ -8,11 +11,50 @@ pub trait Error<'a>: Debug + PartialEq {
     /// If the error is fatal, we won't be able to try again.
     fn is_fatal(&self) -> bool;
 
+    /// If the error is a failure, no alternative should be tried.
+    fn is_failure(&self) -> bool {
+        false
+    }
+
+    /// Turn an unexpected or custom error into a failure.
+    ///
+    /// By default the error is returned as is, never becoming a failure.
+    fn cut(self) -> Self
+    where
+        Self: Sized,
+    {
+        self
+    }
+
     /// Create a new unexpected error with details.
     fn unexpected(unexpected: Unexpected<'a, Self::Token>) -> Self;
 
     /// Create a new incomplete error with a requirement.
     fn incomplete(requirement: Requirement) -> Self;
+
+    /// Create a new error from a user-defined error.
+    ///
+    /// By default the user-defined error is dropped, leaving an unexpected
+    /// error describing the value as invalid.
+    fn custom(_custom: CustomError) -> Self
+    where
+        Self: Sized + 'a,
+    {
+        Self::unexpected(Unexpected {
+            unexpected: TokenTag::Tag(&[]),
+            expecting: ExpectedHint::Description("valid value"),
+        })
+    }
+
+    /// Merge with an error from an alternative that failed at the same point.
+    ///
+    /// By default the first error is kept.
+    fn merge(self, _other: Self) -> Self
+    where
+        Self: Sized,
+    {
+        self
+    }
 }
 
 impl<'a, T> Error<'a> for ErrorReason<'a, T>
@@ -25,6 +67,14 @@ where
         self.is_fatal()
     }
 
+    fn is_failure(&self) -> bool {
+        self.is_failure()
+    }
+
+    fn cut(self) -> Self {
+        self.cut()
+    }
+
     fn unexpected(unexpected: Unexpected<'a, Self::Token>) -> Self {
         ErrorReason::Unexpected(unexpected)
     }
@@ -33,19 +83,96 @@ where
     fn incomplete(requirement: Requirement) -> Self {
         ErrorReason::Incomplete(requirement)
     }
+
+    fn custom(custom: CustomError) -> Self {
+        ErrorReason::Custom(custom)
+    }
+
+    fn merge(self, other: Self) -> Self {
+        self.merge(other)
+    }
 }
 
 #[derive(Clone, Debug, PartialEq)]
 pub enum ErrorReason<'a, T: Token> {
     Incomplete(Requirement),
     Unexpected(Unexpected<'a, T>),
+    /// A user-defined error, such as for a value that parsed but is invalid.
+    Custom(CustomError),
+    /// An error past a point of no return, so rather than backtracking to
+    /// try alternatives, parsing fails.
+    Failure(Box<ErrorReason<'a, T>>),
 }
 
 impl<'a, T: Token> ErrorReason<'a, T> {
     pub fn is_fatal(&self) -> bool {
+        !matches!(self, ErrorReason::Incomplete(_))
+    }
+
+    pub fn is_failure(&self) -> bool {
+        matches!(self, ErrorReason::Failure(_))
+    }
+
+    /// Turn an unexpected or custom error into a failure.
+    ///
+    /// An incomplete error is kept, as more input may still succeed.
+    pub fn cut(self) -> Self {
+        match self {
+            reason @ (ErrorReason::Unexpected(_) | ErrorReason::Custom(_)) => {
+                ErrorReason::Failure(Box::new(reason))
+            }
+            reason => reason,
+        }
+    }
+
+    /// Returns the reason the error would have had if not a failure.
+    pub fn uncut(&self) -> &Self {
+        match self {
+            ErrorReason::Failure(reason) => reason,
+            reason => reason,
+        }
+    }
+
+    /// Returns what was unexpected, if the input was.
+    pub fn unexpected_mut(&mut self) -> Option<&mut Unexpected<'a, T>> {
         match self {
-            ErrorReason::Incomplete(_) => false,
-            _ => true,
+            ErrorReason::Unexpected(unexpected) => Some(unexpected),
+            ErrorReason::Failure(reason) => reason.unexpected_mut(),
+            _ => None,
+        }
+    }
+
+    /// Returns the user-defined error, if it is one.
+    pub fn custom(&self) -> Option<&CustomError> {
+        match self.uncut() {
+            ErrorReason::Custom(custom) => Some(custom),
+            _ => None,
+        }
+    }
+
+    /// Merge with an error from an alternative that failed at the same point.
+    ///
+    /// A failure takes precedence as no alternative could succeed, then an
+    /// incomplete error as the alternative may still succeed with more input,
+    /// then a custom error as it is more specific, otherwise what was
+    /// expected is combined.
+    pub fn merge(self, other: Self) -> Self {
+        match (self, other) {
+            (failure @ ErrorReason::Failure(_), _) => failure,
+            (_, failure @ ErrorReason::Failure(_)) => failure,
+            (ErrorReason::Incomplete(a), ErrorReason::Incomplete(b)) => {
+                ErrorReason::Incomplete(a.merge(b))
+            }
+            (incomplete @ ErrorReason::Incomplete(_), _) => incomplete,
+            (_, incomplete @ ErrorReason::Incomplete(_)) => incomplete,
+            (custom @ ErrorReason::Custom(_), _) => custom,
+            (_, custom @ ErrorReason::Custom(_)) => custom,
+            (ErrorReason::Unexpected(a), ErrorReason::Unexpected(b)) => {
+                ErrorReason::Unexpected(Unexpected {
+                    unexpected: a.unexpected,
+                    expecting: a.expecting.merge(b.expecting),
+                })
+            }
         }
     }
 }
@@ -62,12 +189,234 @@ pub enum ExpectedHint<'a, T: Token> {
     Token(T),
     Tag(&'a [T]),
     Description(&'a str),
-    OneOf(&'a [ExpectedHint<'a, T>]),
+    Class(TokenClass<'a>),
+    OneOf(Vec<ExpectedHint<'a, T>>),
+}
+
+impl<'a, T: Token> ExpectedHint<'a, T> {
+    /// Combine two hints into one of either, flattening any nested `OneOf`.
+    pub fn merge(self, other: Self) -> Self {
+        let mut hints = match self {
+            ExpectedHint::None => return other,
+            ExpectedHint::OneOf(hints) => hints,
+            hint => vec![hint],
+        };
+        match other {
+            ExpectedHint::None => {}
+            ExpectedHint::OneOf(others) => hints.extend(others),
+            hint => hints.push(hint),
+        }
+        if hints.len() == 1 {
+            hints.pop().unwrap()
+        } else {
+            ExpectedHint::OneOf(hints)
+        }
+    }
 }
 
 #[derive(Clone, Debug, PartialEq)]
 pub enum Requirement {
     Exact(usize),
     Between(usize, usize),
+    AtLeast(usize),
     Unknown,
 }
+
+impl Requirement {
+    /// Combine two requirements into one that covers both.
+    pub fn merge(self, other: Self) -> Self {
+        let (a_min, a_max) = match self.bounds() {
+            Some(bounds) => bounds,
+            None => return Requirement::Unknown,
+        };
+        let (b_min, b_max) = match other.bounds() {
+            Some(bounds) => bounds,
+            None => return Requirement::Unknown,
+        };
+        let min = a_min.min(b_min);
+        match (a_max, b_max) {
+            (Some(a_max), Some(b_max)) if min == a_max.max(b_max) => Requirement::Exact(min),
+            (Some(a_max), Some(b_max)) => Requirement::Between(min, a_max.max(b_max)),
+            _ => Requirement::AtLeast(min),
+        }
+    }
+
+    /// Returns the fewest tokens that could satisfy the requirement.
+    ///
+    /// An unknown requirement needs at least one more token.
+    pub fn minimum(&self) -> usize {
+        match *self {
+            Requirement::Exact(n) | Requirement::Between(n, _) | Requirement::AtLeast(n) => n,
+            Requirement::Unknown => 1,
+        }
+    }
+
+    fn bounds(&self) -> Option<(usize, Option<usize>)> {
+        match *self {
+            Requirement::Exact(n) => Some((n, Some(n))),
+            Requirement::Between(min, max) => Some((min, Some(max))),
+            Requirement::AtLeast(n) => Some((n, None)),
+            Requirement::Unknown => None,
+        }
+    }
+}
+
+impl<'a, T> Display for ErrorReason<'a, T>
+where
+    T: Token,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ErrorReason::Incomplete(requirement) => {
+                write!(f, "incomplete input, requires {}", requirement)
+            }
+            ErrorReason::Unexpected(unexpected) => Display::fmt(unexpected, f),
+            ErrorReason::Custom(custom) => Display::fmt(custom, f),
+            ErrorReason::Failure(reason) => Display::fmt(reason, f),
+        }
+    }
+}
+
+/// A user-defined error, kept alongside errors from the input.
+///
+/// Cheap to clone, sharing the error. Errors are equal only if they are
+/// the same error, as the user-defined type need not be comparable.
+#[derive(Clone)]
+pub struct CustomError {
+    error: Arc<dyn error::Error + Send + Sync>,
+}
+
+impl CustomError {
+    /// Create a custom error from a user-defined error.
+    pub fn new<E>(error: E) -> Self
+    where
+        E: error::Error + Send + Sync + 'static,
+    {
+        Self {
+            error: Arc::new(error),
+        }
+    }
+
+    /// Returns the user-defined error.
+    pub fn get(&self) -> &(dyn error::Error + Send + Sync + 'static) {
+        &*self.error
+    }
+
+    /// Returns the user-defined error if it is of type `E`.
+    pub fn downcast_ref<E>(&self) -> Option<&E>
+    where
+        E: error::Error + 'static,
+    {
+        self.error.downcast_ref()
+    }
+}
+
+impl PartialEq for CustomError {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.error, &other.error)
+    }
+}
+
+impl Debug for CustomError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Debug::fmt(&self.error, f)
+    }
+}
+
+impl Display for CustomError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Display::fmt(&self.error, f)
+    }
+}
+
+impl<'a, T> Display for Unexpected<'a, T>
+where
+    T: Token,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // An empty tag has nothing to show as found, such as at the end.
+        match (&self.expecting, &self.unexpected) {
+            (ExpectedHint::None, TokenTag::Tag([])) => f.write_str("unexpected input"),
+            (ExpectedHint::None, unexpected) => write!(f, "unexpected {}", unexpected),
+            (expecting, TokenTag::Tag([])) => write!(f, "expected {}", expecting),
+            (expecting, unexpected) => write!(f, "expected {}, found {}", expecting, unexpected),
+        }
+    }
+}
+
+impl<'a, T> Display for ExpectedHint<'a, T>
+where
+    T: Token,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExpectedHint::None => f.write_str("anything"),
+            ExpectedHint::Token(token) => fmt_tokens(std::slice::from_ref(token), '\'', f),
+            ExpectedHint::Tag(tag) => fmt_tokens(tag, '"', f),
+            ExpectedHint::Description(description) => f.write_str(description),
+            ExpectedHint::Class(class) => Display::fmt(class, f),
+            ExpectedHint::OneOf(hints) => {
+                f.write_str("one of ")?;
+                for (i, hint) in hints.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(", ")?;
+                    }
+                    Display::fmt(hint, f)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl Display for Requirement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Requirement::Exact(1) => f.write_str("1 more token"),
+            Requirement::Exact(n) => write!(f, "{} more tokens", n),
+            Requirement::Between(min, max) => write!(f, "between {} and {} more tokens", min, max),
+            Requirement::AtLeast(1) => f.write_str("at least 1 more token"),
+            Requirement::AtLeast(n) => write!(f, "at least {} more tokens", n),
+            Requirement::Unknown => f.write_str("more input"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// An error implementing only what is required.
+    #[derive(Debug, PartialEq)]
+    struct MinimalError(Option<Requirement>);
+
+    impl<'a> Error<'a> for MinimalError {
+        type Token = u8;
+
+        fn is_fatal(&self) -> bool {
+            self.0.is_none()
+        }
+
+        fn unexpected(_unexpected: Unexpected<'a, u8>) -> Self {
+            MinimalError(None)
+        }
+
+        fn incomplete(requirement: Requirement) -> Self {
+            MinimalError(Some(requirement))
+        }
+    }
+
+    #[test]
+    fn test_error_defaults() {
+        let custom = CustomError::new(fmt::Error);
+        let err = <MinimalError as Error<'_>>::custom(custom);
+
+        assert!(err.is_fatal());
+        assert!(!err.is_failure());
+        assert_eq!(err.cut(), MinimalError(None));
+        assert_eq!(
+            MinimalError(None).merge(MinimalError(Some(Requirement::Unknown))),
+            MinimalError(None)
+        );
+    }
+}
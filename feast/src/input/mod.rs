@@ -1,14 +1,25 @@
+mod bits;
 mod capture;
+mod class;
 mod error;
+mod predicate;
+mod search;
 mod slice;
+mod stream;
+mod text;
 mod token;
 
 use std::fmt::Debug;
-use std::ops::Index;
 
+pub use self::bits::*;
 pub use self::capture::*;
+pub use self::class::*;
 pub use self::error::*;
+pub use self::predicate::*;
+pub use self::search::*;
 pub use self::slice::*;
+pub use self::stream::*;
+pub use self::text::*;
 pub use self::token::*;
 
 pub trait InputMarker {
@@ -26,7 +37,8 @@ where
 {
 }
 
-pub trait Input<'i>: Sized + Debug {
+/// Inputs are captures, complete if no more tokens can arrive.
+pub trait Input<'i>: Sized + Clone + Debug + Capture {
     type Mark;
     /// The smallest unit of data the input provides.
     type Token: Token;
@@ -53,13 +65,32 @@ pub trait Input<'i>: Sized + Debug {
         E: Error<'i, Token = Self::Token>;
 
     fn iter(&self) -> Self::Iterator;
+
+    /// Returns the remaining tokens if they are contiguous, so searches can
+    /// run over the slice rather than iterate.
+    fn as_contiguous(&self) -> Option<&'i [Self::Token]> {
+        None
+    }
 }
 
-pub trait ExactSizeInput<'i>:
-    Input<'i> + Capture + Index<usize, Output = InputToken<'i, Self>>
-{
+/// An input with a known length, such as a section.
+pub trait ExactSizeInput<'i>: Input<'i> + Capture {
     /// Returns the length of the input.
     fn len(&self) -> usize;
+
+    /// Returns the token at an index, or `None` past the end of the input.
+    ///
+    /// Tokens are returned by value, so inputs decoding their tokens can
+    /// index them. By default the input is iterated up to the index.
+    fn get(&self, idx: usize) -> Option<Self::Token> {
+        self.iter().nth(idx)
+    }
+}
+
+/// Inputs backed by a contiguous slice of tokens.
+pub trait AsSlice<'i>: Input<'i> {
+    /// Returns the remaining tokens of the input.
+    fn as_slice(&self) -> &'i [Self::Token];
 }
 
 /// Helper to reference an Input's `Token` type.
@@ -0,0 +1,78 @@
+use super::{find_byte, find_byte2, find_byte3, ExpectedHint, Token};
+
+/// A test of whether a token matches.
+///
+/// Closures taking a token reference are predicates, searched a token at a
+/// time. Only a `u8`, a `[u8; 2]`, a `[u8; 3]` or a `ByteSet` of up to three
+/// bytes searches byte slices a word at a time, so pass bytes rather than a
+/// closure comparing them to get the faster search. Predicates that know
+/// what they match describe it when a token does not.
+pub trait TokenPredicate<'a, T>
+where
+    T: Token,
+{
+    /// Returns whether the token matches.
+    fn matches(&self, token: &T) -> bool;
+
+    /// Returns the index of the first token matching.
+    fn find_in(&self, tokens: &[T]) -> Option<usize> {
+        tokens.iter().position(|token| self.matches(token))
+    }
+
+    /// Returns what a matching token would be.
+    fn expecting(&self) -> ExpectedHint<'a, T> {
+        ExpectedHint::None
+    }
+}
+
+impl<'a, T, F> TokenPredicate<'a, T> for F
+where
+    T: Token,
+    F: Fn(&T) -> bool,
+{
+    fn matches(&self, token: &T) -> bool {
+        self(token)
+    }
+}
+
+impl<'a> TokenPredicate<'a, u8> for u8 {
+    fn matches(&self, token: &u8) -> bool {
+        self == token
+    }
+
+    fn find_in(&self, tokens: &[u8]) -> Option<usize> {
+        find_byte(*self, tokens)
+    }
+
+    fn expecting(&self) -> ExpectedHint<'a, u8> {
+        ExpectedHint::Token(*self)
+    }
+}
+
+impl<'a> TokenPredicate<'a, u8> for [u8; 2] {
+    fn matches(&self, token: &u8) -> bool {
+        self.contains(token)
+    }
+
+    fn find_in(&self, tokens: &[u8]) -> Option<usize> {
+        find_byte2(self[0], self[1], tokens)
+    }
+
+    fn expecting(&self) -> ExpectedHint<'a, u8> {
+        ExpectedHint::OneOf(self.iter().copied().map(ExpectedHint::Token).collect())
+    }
+}
+
+impl<'a> TokenPredicate<'a, u8> for [u8; 3] {
+    fn matches(&self, token: &u8) -> bool {
+        self.contains(token)
+    }
+
+    fn find_in(&self, tokens: &[u8]) -> Option<usize> {
+        find_byte3(self[0], self[1], self[2], tokens)
+    }
+
+    fn expecting(&self) -> ExpectedHint<'a, u8> {
+        ExpectedHint::OneOf(self.iter().copied().map(ExpectedHint::Token).collect())
+    }
+}
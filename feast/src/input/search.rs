@@ -0,0 +1,157 @@
+use std::convert::TryInto;
+use std::mem;
+
+const WORD: usize = mem::size_of::<usize>();
+const LO: usize = usize::from_ne_bytes([0x01; WORD]);
+const HI: usize = usize::from_ne_bytes([0x80; WORD]);
+
+/// Returns a word with every byte set to `byte`.
+fn splat(byte: u8) -> usize {
+    usize::from_ne_bytes([byte; WORD])
+}
+
+/// Returns a word with the high bit set of at least the lowest zero byte,
+/// or no bits set if no byte is zero.
+fn zero_bytes(word: usize) -> usize {
+    word.wrapping_sub(LO) & !word & HI
+}
+
+/// Finds the first byte matching, checking a word at a time for whether
+/// any byte within it could.
+///
+/// `word_matches` must be true for every word containing a matching byte.
+fn find_by_word<W, B>(haystack: &[u8], word_matches: W, byte_matches: B) -> Option<usize>
+where
+    W: Fn(usize) -> bool,
+    B: Fn(u8) -> bool,
+{
+    let mut chunks = haystack.chunks_exact(WORD);
+    let mut offset = 0;
+    for chunk in &mut chunks {
+        let word = usize::from_ne_bytes(chunk.try_into().unwrap());
+        if word_matches(word) {
+            if let Some(idx) = chunk.iter().position(|&byte| byte_matches(byte)) {
+                return Some(offset + idx);
+            }
+        }
+        offset += WORD;
+    }
+    chunks
+        .remainder()
+        .iter()
+        .position(|&byte| byte_matches(byte))
+        .map(|idx| offset + idx)
+}
+
+/// Returns the index of the first occurrence of a byte.
+pub fn find_byte(a: u8, haystack: &[u8]) -> Option<usize> {
+    let va = splat(a);
+    find_by_word(
+        haystack,
+        |word| zero_bytes(word ^ va) != 0,
+        |byte| byte == a,
+    )
+}
+
+/// Returns the index of the first occurrence of either byte.
+pub fn find_byte2(a: u8, b: u8, haystack: &[u8]) -> Option<usize> {
+    let (va, vb) = (splat(a), splat(b));
+    find_by_word(
+        haystack,
+        |word| zero_bytes(word ^ va) | zero_bytes(word ^ vb) != 0,
+        |byte| byte == a || byte == b,
+    )
+}
+
+/// Returns the index of the first occurrence of any of three bytes.
+pub fn find_byte3(a: u8, b: u8, c: u8, haystack: &[u8]) -> Option<usize> {
+    let (va, vb, vc) = (splat(a), splat(b), splat(c));
+    find_by_word(
+        haystack,
+        |word| zero_bytes(word ^ va) | zero_bytes(word ^ vb) | zero_bytes(word ^ vc) != 0,
+        |byte| byte == a || byte == b || byte == c,
+    )
+}
+
+/// Returns the index of the first occurrence of a byte string.
+///
+/// Candidates are found by searching for the first byte of the needle. An
+/// empty needle occurs at the start.
+///
+/// Each candidate is compared against the whole needle, so the worst case
+/// takes time proportional to the haystack length times the needle length,
+/// such as for `aab` within a run of `a`. Needles are expected to be short
+/// tags, where this is cheaper than the setup of a two-way search.
+pub fn find_bytes(needle: &[u8], haystack: &[u8]) -> Option<usize> {
+    let (&first, rest) = match needle.split_first() {
+        Some(split) => split,
+        None => return Some(0),
+    };
+    let last_start = haystack.len().checked_sub(needle.len())?;
+    let mut start = 0;
+    while start <= last_start {
+        let idx = start + find_byte(first, &haystack[start..=last_start])?;
+        if haystack[idx + 1..].starts_with(rest) {
+            return Some(idx);
+        }
+        start = idx + 1;
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn naive_find(needle: &[u8], haystack: &[u8]) -> Option<usize> {
+        if needle.is_empty() {
+            return Some(0);
+        }
+        haystack
+            .windows(needle.len())
+            .position(|window| window == needle)
+    }
+
+    #[test]
+    fn test_find_byte_alignments() {
+        let mut haystack = vec![b'a'; 40];
+        assert_eq!(find_byte(b'x', &haystack), None);
+        for idx in 0..haystack.len() {
+            haystack[idx] = b'x';
+            for start in 0..=idx {
+                assert_eq!(find_byte(b'x', &haystack[start..]), Some(idx - start));
+            }
+            haystack[idx] = b'a';
+        }
+    }
+
+    #[test]
+    fn test_find_byte_high_bytes() {
+        let haystack = [0x80, 0xff, 0x7f, 0x00, 0x81, 0x01, 0xfe, 0x80, 0x00];
+
+        assert_eq!(find_byte(0x00, &haystack), Some(3));
+        assert_eq!(find_byte(0x01, &haystack), Some(5));
+        assert_eq!(find_byte(0xfe, &haystack), Some(6));
+        assert_eq!(find_byte(0x02, &haystack), None);
+    }
+
+    #[test]
+    fn test_find_byte2_and_byte3() {
+        let haystack = b"the quick brown fox; jumps\nover";
+
+        assert_eq!(find_byte2(b';', b'\n', haystack), Some(19));
+        assert_eq!(find_byte2(b'\n', b'z', haystack), Some(26));
+        assert_eq!(find_byte3(b'z', b'\n', b'q', haystack), Some(4));
+        assert_eq!(find_byte3(b'0', b'1', b'2', haystack), None);
+    }
+
+    #[test]
+    fn test_find_bytes() {
+        let haystack = b"aab aaab aaaab -->  --> end";
+
+        for needle in [&b""[..], b"a", b"aaab", b"-->", b" end", b"end!", b"aaaaab"] {
+            assert_eq!(find_bytes(needle, haystack), naive_find(needle, haystack));
+        }
+        assert_eq!(find_bytes(b"ab", b"a"), None);
+    }
+}
@@ -61,6 +61,10 @@ where
     fn iter(&self) -> Self::Iterator {
         SliceIterator::from(self.0)
     }
+
+    fn as_contiguous(&self) -> Option<&'i [Self::Token]> {
+        Some(self.0)
+    }
 }
 
 impl<'i, T> ExactSizeInput<'i> for SliceInput<'i, T>
@@ -70,6 +74,19 @@ where
     fn len(&self) -> usize {
         self.0.len()
     }
+
+    fn get(&self, idx: usize) -> Option<T> {
+        self.0.get(idx).cloned()
+    }
+}
+
+impl<'i, T> AsSlice<'i> for SliceInput<'i, T>
+where
+    T: Token,
+{
+    fn as_slice(&self) -> &'i [T] {
+        self.0
+    }
 }
 
 impl<'i, T> Capture for SliceInput<'i, T>
@@ -82,9 +99,7 @@ where
         true
     }
 
-    fn resolve(&mut self) {
-        ()
-    }
+    fn resolve(&mut self) {}
 
     fn into_value(self) -> Self::Value {
         self
@@ -106,7 +121,7 @@ impl<'i, T> AsRef<[T]> for SliceInput<'i, T>
 where
     T: Token,
 {
-    fn as_ref<'b>(&'b self) -> &'b [T] {
+    fn as_ref(&self) -> &[T] {
         self.0
     }
 }
@@ -0,0 +1,216 @@
+use super::*;
+
+use std::ops::Index;
+
+/// Input from a stream, where more tokens may still arrive.
+///
+/// Sections split at an exact size are always complete. Sections split at
+/// a mark that reaches the end of an incomplete stream are indeterminate,
+/// as more tokens arriving may have extended them.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct StreamInput<'i, T: Token> {
+    tokens: &'i [T],
+    complete: bool,
+}
+
+impl<'i, T> StreamInput<'i, T>
+where
+    T: Token,
+{
+    /// Create an input from the tokens received so far, with more to come.
+    pub fn partial(tokens: &'i [T]) -> Self {
+        Self {
+            tokens,
+            complete: false,
+        }
+    }
+
+    /// Create an input from all the tokens in the stream.
+    pub fn complete(tokens: &'i [T]) -> Self {
+        Self {
+            tokens,
+            complete: true,
+        }
+    }
+
+    /// Returns the tokens available from the input.
+    pub fn tokens(&self) -> &'i [T] {
+        self.tokens
+    }
+}
+
+impl<'i, T> Input<'i> for StreamInput<'i, T>
+where
+    T: Token,
+{
+    type Mark = usize;
+    type Token = T;
+    type Section = Self;
+    type Iterator = SliceIterator<'i, T>;
+
+    fn is_empty(&self) -> bool {
+        self.tokens.is_empty()
+    }
+
+    fn split_first<E>(self) -> Result<(Self::Token, Self), E>
+    where
+        E: Error<'i, Token = Self::Token>,
+    {
+        self.tokens
+            .split_first()
+            .map(|(token, rest)| {
+                let rest = Self {
+                    tokens: rest,
+                    complete: self.complete,
+                };
+                (token.clone(), rest)
+            })
+            .ok_or_else(|| E::incomplete(Requirement::Exact(1)))
+    }
+
+    fn split_at<E>(self, mid: usize) -> Result<(Self::Section, Self), E>
+    where
+        E: Error<'i, Token = Self::Token>,
+    {
+        if mid > self.len() {
+            Err(E::incomplete(Requirement::Exact(mid - self.len())))
+        } else {
+            let (consumed, rest) = self.tokens.split_at(mid);
+            let rest = Self {
+                tokens: rest,
+                complete: self.complete,
+            };
+            Ok((Self::complete(consumed), rest))
+        }
+    }
+
+    fn split_mark<E>(self, mark: Self::Mark) -> Result<(Self::Section, Self), E>
+    where
+        E: Error<'i, Token = Self::Token>,
+    {
+        let complete = self.complete || mark != self.len();
+        let (mut section, rest) = self.split_at::<E>(mark)?;
+        section.complete = complete;
+        Ok((section, rest))
+    }
+
+    fn iter(&self) -> Self::Iterator {
+        SliceIterator::from(self.tokens)
+    }
+
+    fn as_contiguous(&self) -> Option<&'i [Self::Token]> {
+        Some(self.tokens)
+    }
+}
+
+impl<'i, T> ExactSizeInput<'i> for StreamInput<'i, T>
+where
+    T: Token,
+{
+    fn len(&self) -> usize {
+        self.tokens.len()
+    }
+
+    fn get(&self, idx: usize) -> Option<T> {
+        self.tokens.get(idx).cloned()
+    }
+}
+
+impl<'i, T> AsSlice<'i> for StreamInput<'i, T>
+where
+    T: Token,
+{
+    fn as_slice(&self) -> &'i [T] {
+        self.tokens
+    }
+}
+
+impl<'i, T> Capture for StreamInput<'i, T>
+where
+    T: Token,
+{
+    type Value = Self;
+
+    fn is_complete(&self) -> bool {
+        self.complete
+    }
+
+    fn resolve(&mut self) {
+        self.complete = true;
+    }
+
+    fn into_value(self) -> Self::Value {
+        self
+    }
+}
+
+impl<'i, T> BuildableCapture for StreamInput<'i, T>
+where
+    T: Token,
+{
+    fn from_determinate(value: Self) -> Self {
+        Self::complete(value.tokens)
+    }
+
+    fn from_indeterminate(value: Self) -> Self {
+        value
+    }
+}
+
+impl<'i, T> Index<usize> for StreamInput<'i, T>
+where
+    T: Token,
+{
+    type Output = T;
+
+    fn index(&self, idx: usize) -> &Self::Output {
+        &self.tokens[idx]
+    }
+}
+
+impl<'i, T> AsRef<[T]> for StreamInput<'i, T>
+where
+    T: Token,
+{
+    fn as_ref(&self) -> &[T] {
+        self.tokens
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type MockToken = u8;
+    type MockError = ErrorReason<'static, MockToken>;
+
+    fn partial(input: &'static [u8]) -> StreamInput<'static, MockToken> {
+        StreamInput::partial(input)
+    }
+
+    #[test]
+    fn test_stream_input_split_at() {
+        let (section, rest) = partial(b"hello").split_at::<MockError>(5).unwrap();
+
+        assert!(section.is_complete());
+        assert!(!rest.is_complete());
+        assert_eq!(
+            partial(b"hel").split_at::<MockError>(5),
+            Err(ErrorReason::Incomplete(Requirement::Exact(2)))
+        );
+    }
+
+    #[test]
+    fn test_stream_input_split_mark() {
+        let (section, _) = partial(b"hello").split_mark::<MockError>(2).unwrap();
+        assert!(section.is_complete());
+
+        let (section, _) = partial(b"hello").split_mark::<MockError>(5).unwrap();
+        assert!(!section.is_complete());
+
+        let (section, _) = StreamInput::complete(&b"hello"[..])
+            .split_mark::<MockError>(5)
+            .unwrap();
+        assert!(section.is_complete());
+    }
+}
@@ -0,0 +1,262 @@
+use super::*;
+
+/// Input from a string, with tokens decoded as chars.
+///
+/// Marks are byte offsets into the string, so splitting at a mark is cheap.
+/// Splitting at an exact size counts chars, as does the length, which is
+/// counted once when the input is created and kept as it is split.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct StrInput<'i> {
+    text: &'i str,
+    chars: usize,
+}
+
+impl<'i> StrInput<'i> {
+    pub fn empty() -> Self {
+        Self { text: "", chars: 0 }
+    }
+
+    /// Returns the remaining text of the input.
+    pub fn as_str(&self) -> &'i str {
+        self.text
+    }
+
+    /// Splits at a byte offset on a char boundary, counting the chars split
+    /// off unless the text is all ASCII.
+    fn split_bytes(self, idx: usize) -> (Self, Self) {
+        let (consumed, rest) = self.text.split_at(idx);
+        let consumed_chars = if self.text.len() == self.chars {
+            idx
+        } else {
+            consumed.chars().count()
+        };
+        (
+            Self {
+                text: consumed,
+                chars: consumed_chars,
+            },
+            Self {
+                text: rest,
+                chars: self.chars - consumed_chars,
+            },
+        )
+    }
+}
+
+impl<'i> Input<'i> for StrInput<'i> {
+    type Mark = usize;
+    type Token = char;
+    type Section = Self;
+    type Iterator = StrIterator<'i>;
+
+    fn is_empty(&self) -> bool {
+        self.text.is_empty()
+    }
+
+    fn split_first<E>(self) -> Result<(Self::Token, Self), E>
+    where
+        E: Error<'i, Token = Self::Token>,
+    {
+        let token = self
+            .text
+            .chars()
+            .next()
+            .ok_or_else(|| E::incomplete(Requirement::Exact(1)))?;
+        let rest = Self {
+            text: &self.text[token.len_utf8()..],
+            chars: self.chars - 1,
+        };
+        Ok((token, rest))
+    }
+
+    fn split_at<E>(self, mid: usize) -> Result<(Self::Section, Self), E>
+    where
+        E: Error<'i, Token = Self::Token>,
+    {
+        if mid > self.chars {
+            return Err(E::incomplete(Requirement::Exact(mid - self.chars)));
+        }
+        let idx = if self.text.len() == self.chars {
+            mid
+        } else {
+            self.text
+                .char_indices()
+                .nth(mid)
+                .map_or(self.text.len(), |(idx, _)| idx)
+        };
+        let (consumed, rest) = self.text.split_at(idx);
+        let section = Self {
+            text: consumed,
+            chars: mid,
+        };
+        let rest = Self {
+            text: rest,
+            chars: self.chars - mid,
+        };
+        Ok((section, rest))
+    }
+
+    /// Splits at a byte offset, which must lie on a char boundary.
+    fn split_mark<E>(self, mark: Self::Mark) -> Result<(Self::Section, Self), E>
+    where
+        E: Error<'i, Token = Self::Token>,
+    {
+        if mark > self.text.len() {
+            // Each char missing is between one and four bytes.
+            let missing = mark - self.text.len();
+            let requirement = Requirement::Between(missing.div_ceil(4), missing);
+            Err(E::incomplete(requirement))
+        } else {
+            Ok(self.split_bytes(mark))
+        }
+    }
+
+    fn iter(&self) -> Self::Iterator {
+        StrIterator::from(self.text)
+    }
+}
+
+impl<'i> ExactSizeInput<'i> for StrInput<'i> {
+    /// Returns the length of the input in chars.
+    fn len(&self) -> usize {
+        self.chars
+    }
+}
+
+impl<'i> Capture for StrInput<'i> {
+    type Value = Self;
+
+    fn is_complete(&self) -> bool {
+        true
+    }
+
+    fn resolve(&mut self) {}
+
+    fn into_value(self) -> Self::Value {
+        self
+    }
+}
+
+impl<'i> AsRef<str> for StrInput<'i> {
+    fn as_ref(&self) -> &str {
+        self.text
+    }
+}
+
+impl<'i> From<&'i str> for StrInput<'i> {
+    fn from(text: &'i str) -> Self {
+        Self {
+            text,
+            chars: text.chars().count(),
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+/// Iterates the chars of a string, marking byte offsets.
+#[derive(Clone)]
+pub struct StrIterator<'a> {
+    cursor: usize,
+    text: &'a str,
+}
+
+impl<'a> StrIterator<'a> {
+    pub fn at_end(&self) -> bool {
+        self.cursor == self.text.len()
+    }
+}
+
+impl<'a> InputMarker for StrIterator<'a> {
+    type Mark = usize;
+
+    fn mark(&self) -> Self::Mark {
+        self.cursor
+    }
+}
+
+impl<'a> Iterator for StrIterator<'a> {
+    type Item = char;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let next = self.text[self.cursor..].chars().next()?;
+        self.cursor += next.len_utf8();
+        Some(next)
+    }
+}
+
+impl<'a> From<&'a str> for StrIterator<'a> {
+    fn from(text: &'a str) -> Self {
+        Self { cursor: 0, text }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type MockError = ErrorReason<'static, char>;
+
+    fn str_input(input: &'static str) -> StrInput<'static> {
+        StrInput::from(input)
+    }
+
+    #[test]
+    fn test_str_input_split_first() {
+        assert_eq!(
+            str_input("éa").split_first::<MockError>(),
+            Ok(('é', str_input("a")))
+        );
+
+        assert_eq!(
+            StrInput::empty().split_first::<MockError>(),
+            Err(ErrorReason::Incomplete(Requirement::Exact(1)))
+        );
+    }
+
+    #[test]
+    fn test_str_input_split_at() {
+        assert_eq!(
+            str_input("héllo").split_at::<MockError>(2),
+            Ok((str_input("hé"), str_input("llo")))
+        );
+
+        assert_eq!(
+            str_input("héllo").split_at::<MockError>(5),
+            Ok((str_input("héllo"), StrInput::empty()))
+        );
+
+        assert_eq!(
+            str_input("héllo").split_at::<MockError>(7),
+            Err(ErrorReason::Incomplete(Requirement::Exact(2)))
+        );
+    }
+
+    #[test]
+    fn test_str_input_len() {
+        let input = str_input("héllo");
+
+        assert_eq!(input.len(), 5);
+        assert_eq!(input.get(1), Some('é'));
+        assert_eq!(input.get(5), None);
+
+        let (section, rest) = input.split_at::<MockError>(2).unwrap();
+        assert_eq!((section.len(), rest.len()), (2, 3));
+        let (section, rest) = input.split_mark::<MockError>(3).unwrap();
+        assert_eq!((section.len(), rest.len()), (2, 3));
+    }
+
+    #[test]
+    fn test_str_input_marker() {
+        let input = str_input("héllo");
+        let mut iter = input.iter();
+
+        assert_eq!(iter.next(), Some('h'));
+        assert_eq!(iter.next(), Some('é'));
+        assert_eq!(iter.mark(), 3);
+        assert_eq!(
+            input.split_mark::<MockError>(iter.mark()),
+            Ok((str_input("hé"), str_input("llo")))
+        );
+    }
+}
@@ -1,15 +1,23 @@
-use super::Capture;
-use std::fmt::Debug;
+use super::{find_bytes, Capture};
+use std::fmt::{self, Debug, Display};
 
 pub trait IntoBytes {
     type Bytes: AsRef<[u8]>;
 
-    fn into_bytes<'a>(self) -> Self::Bytes;
+    fn into_bytes(self) -> Self::Bytes;
 }
 
 pub trait Token: Sized + Capture + Debug + Clone + IntoBytes + Eq {
     fn byte_size() -> Option<usize>;
     fn is_ascii(&self) -> bool;
+
+    /// Returns the index of the first occurrence of a tag within tokens.
+    fn find_tag(tokens: &[Self], tag: &[Self]) -> Option<usize> {
+        if tag.is_empty() {
+            return Some(0);
+        }
+        tokens.windows(tag.len()).position(|window| window == tag)
+    }
 }
 
 impl Token for u8 {
@@ -20,7 +28,11 @@ impl Token for u8 {
 
     #[inline]
     fn is_ascii(&self) -> bool {
-        return self.is_ascii();
+        self.is_ascii()
+    }
+
+    fn find_tag(tokens: &[Self], tag: &[Self]) -> Option<usize> {
+        find_bytes(tag, tokens)
     }
 }
 
@@ -29,7 +41,7 @@ impl_complete_capture!(u8);
 impl IntoBytes for u8 {
     type Bytes = [u8; 1];
 
-    fn into_bytes<'a>(self) -> Self::Bytes {
+    fn into_bytes(self) -> Self::Bytes {
         [self]
     }
 }
@@ -42,19 +54,55 @@ impl Token for char {
 
     #[inline]
     fn is_ascii(&self) -> bool {
-        return self.is_ascii();
+        self.is_ascii()
     }
 }
 
 impl_complete_capture!(char);
 
 impl IntoBytes for char {
-    type Bytes = [u8; 4];
+    type Bytes = CharBytes;
 
     fn into_bytes(self) -> Self::Bytes {
         let mut bytes = [0u8; 4];
-        self.encode_utf8(&mut bytes[..]);
-        bytes
+        let len = self.encode_utf8(&mut bytes[..]).len();
+        CharBytes { bytes, len }
+    }
+}
+
+/// Bits are tokens of bit inputs, shown as `0` or `1`.
+impl Token for bool {
+    #[inline]
+    fn byte_size() -> Option<usize> {
+        Some(1)
+    }
+
+    #[inline]
+    fn is_ascii(&self) -> bool {
+        true
+    }
+}
+
+impl_complete_capture!(bool);
+
+impl IntoBytes for bool {
+    type Bytes = [u8; 1];
+
+    fn into_bytes(self) -> Self::Bytes {
+        [b'0' + u8::from(self)]
+    }
+}
+
+/// The UTF-8 encoded bytes of a `char`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CharBytes {
+    bytes: [u8; 4],
+    len: usize,
+}
+
+impl AsRef<[u8]> for CharBytes {
+    fn as_ref(&self) -> &[u8] {
+        &self.bytes[..self.len]
     }
 }
 
@@ -69,10 +117,7 @@ where
     T: Token,
 {
     pub fn is_token(&self) -> bool {
-        match self {
-            TokenTag::Token(_) => true,
-            _ => false,
-        }
+        matches!(self, TokenTag::Token(_))
     }
 }
 
@@ -96,3 +141,40 @@ where
         }
     }
 }
+
+impl<'a, T> Display for TokenTag<'a, T>
+where
+    T: Token,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TokenTag::Token(token) => fmt_tokens(std::slice::from_ref(token), '\'', f),
+            TokenTag::Tag(tag) => fmt_tokens(tag, '"', f),
+        }
+    }
+}
+
+/// Formats tokens as quoted text if printable, otherwise as hex bytes.
+pub(crate) fn fmt_tokens<T>(tokens: &[T], quote: char, f: &mut fmt::Formatter<'_>) -> fmt::Result
+where
+    T: Token,
+{
+    let mut bytes = Vec::new();
+    for token in tokens {
+        bytes.extend_from_slice(token.clone().into_bytes().as_ref());
+    }
+    match std::str::from_utf8(&bytes) {
+        Ok(text) if !text.is_empty() && !text.chars().any(char::is_control) => {
+            write!(f, "{}{}{}", quote, text, quote)
+        }
+        _ => {
+            for (i, byte) in bytes.iter().enumerate() {
+                if i > 0 {
+                    f.write_str(" ")?;
+                }
+                write!(f, "0x{:02x}", byte)?;
+            }
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,157 @@
+use tokio::io::{AsyncRead, AsyncReadExt};
+
+use super::{ReadBuffer, ReadError, ReadPass, Step};
+use crate::pass::PassResult;
+
+/// Runs a parser repeatedly over bytes from an async reader.
+///
+/// The async counterpart to [`ReadParser`](super::ReadParser), buffering
+/// bytes only as the parser requires them, up to a maximum.
+pub struct AsyncReadParser<R, F> {
+    reader: R,
+    parser: F,
+    buffer: ReadBuffer,
+}
+
+impl<R, F, O> AsyncReadParser<R, F>
+where
+    R: AsyncRead + Unpin,
+    F: for<'i> Fn(ReadPass<'i>) -> PassResult<'i, ReadPass<'i>, O>,
+{
+    pub fn new(reader: R, parser: F) -> Self {
+        Self {
+            reader,
+            parser,
+            buffer: ReadBuffer::new(),
+        }
+    }
+
+    /// Limit how many bytes are buffered for a single parse.
+    pub fn with_max_buffer(mut self, max_buffer: usize) -> Self {
+        self.buffer.max_buffer = max_buffer;
+        self
+    }
+
+    /// Returns the bytes buffered but not yet parsed.
+    pub fn buffered(&self) -> &[u8] {
+        self.buffer.buffered()
+    }
+
+    /// Consume the parser, returning the reader.
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+
+    /// Parses the next item, reading as many bytes as the parser requires.
+    ///
+    /// Returns `None` once the reader is exhausted and every byte was
+    /// parsed, or after an error was returned.
+    ///
+    /// This is cancel safe: if the future is dropped before completing, the
+    /// bytes read so far stay buffered for the next call.
+    pub async fn next(&mut self) -> Option<Result<O, ReadError>> {
+        loop {
+            match self.buffer.step(&self.parser) {
+                Step::Yield(item) => return item,
+                Step::Fill(required) => {
+                    if let Err(err) = self.fill(required).await {
+                        self.buffer.done = true;
+                        return Some(Err(err));
+                    }
+                }
+            }
+        }
+    }
+
+    async fn fill(&mut self, required: usize) -> Result<(), ReadError> {
+        while !self.buffer.is_filled(required) {
+            let want = self.buffer.reserve(required)?;
+            let filled = self.buffer.bytes.len();
+            // Reads append to the buffer, so a read cancelled while pending
+            // leaves nothing behind.
+            let mut reader = (&mut self.reader).take(want as u64);
+            let read = reader.read_buf(&mut self.buffer.bytes).await;
+            self.buffer.advance(filled, read)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::byte::*;
+    use crate::core::*;
+    use crate::pass::Position;
+
+    use assert_matches::assert_matches;
+    use tokio::io::{duplex, AsyncWriteExt};
+
+    fn frame(pass: ReadPass<'_>) -> PassResult<'_, ReadPass<'_>, Vec<u8>> {
+        let (len, pass) = be_u16(pass)?;
+        let (body, pass) = take_input(len as usize)(pass)?;
+        Ok((body.as_ref().to_vec(), pass))
+    }
+
+    #[tokio::test]
+    async fn test_async_read_parser_frames() {
+        let (mut client, server) = duplex(4);
+        let writer = tokio::spawn(async move {
+            for chunk in [&[0x00][..], &[0x03, b'a'], &[b'b', b'c', 0x00, 0x01], b"d"] {
+                client.write_all(chunk).await.unwrap();
+            }
+        });
+
+        let mut frames = AsyncReadParser::new(server, frame);
+        assert_matches!(frames.next().await, Some(Ok(body)) => assert_eq!(body, b"abc"));
+        assert_matches!(frames.next().await, Some(Ok(body)) => assert_eq!(body, b"d"));
+        writer.await.unwrap();
+        assert_matches!(frames.next().await, None);
+    }
+
+    #[tokio::test]
+    async fn test_async_read_parser_cancelled() {
+        let (mut client, server) = duplex(64);
+        client.write_all(&[0x00]).await.unwrap();
+
+        let mut frames = AsyncReadParser::new(server, frame);
+        // The read for the rest of the length is pending when cancelled.
+        tokio::select! {
+            biased;
+            _ = frames.next() => panic!("parsed a frame without its length"),
+            _ = std::future::ready(()) => {}
+        }
+        assert_eq!(frames.buffered(), &[0x00]);
+
+        client.write_all(&[0x02, b'a', b'b']).await.unwrap();
+        assert_matches!(frames.next().await, Some(Ok(body)) => assert_eq!(body, b"ab"));
+    }
+
+    #[tokio::test]
+    async fn test_async_read_parser_truncated() {
+        let (mut client, server) = duplex(64);
+        client.write_all(&[0x00, 0x05, b'a', b'b']).await.unwrap();
+        drop(client);
+
+        let mut frames = AsyncReadParser::new(server, frame);
+        assert_matches!(
+            frames.next().await,
+            Some(Err(ReadError::Parse { position, .. })) => {
+                assert_eq!(position, Position::new(2));
+            }
+        );
+        assert_matches!(frames.next().await, None);
+    }
+
+    #[tokio::test]
+    async fn test_async_read_parser_buffer_limit() {
+        let (mut client, server) = duplex(64);
+        client.write_all(&[0xff, 0xff]).await.unwrap();
+
+        let mut frames = AsyncReadParser::new(server, frame).with_max_buffer(16);
+        assert_matches!(
+            frames.next().await,
+            Some(Err(ReadError::BufferLimit { max: 16, .. }))
+        );
+    }
+}
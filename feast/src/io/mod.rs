@@ -0,0 +1,350 @@
+#[cfg(feature = "tokio")]
+mod async_read;
+
+#[cfg(feature = "tokio")]
+pub use self::async_read::*;
+
+use std::error::Error as StdError;
+use std::fmt::{self, Display};
+use std::io::{self, Read};
+
+use crate::input::StreamInput;
+use crate::pass::{
+    Error, Pass, PassResult, Position, ScanMemo, StreamPass, StreamPassContext, VerboseError,
+};
+
+/// The default limit on how many bytes a [`ReadParser`] will buffer.
+pub const DEFAULT_MAX_BUFFER: usize = 8 * 1024 * 1024;
+
+/// The least number of bytes requested from a reader at a time.
+const READ_CHUNK: usize = 8 * 1024;
+
+/// The error type of passes run by a [`ReadParser`].
+pub type ReadPassError<'i> = VerboseError<'i, StreamPassContext<'i, u8>>;
+
+/// The pass type run by a [`ReadParser`].
+pub type ReadPass<'i> = StreamPass<'i, u8, ReadPassError<'i>>;
+
+/// An error from parsing a reader.
+#[derive(Debug)]
+pub enum ReadError {
+    /// Reading failed.
+    Io(io::Error),
+    /// Parsing failed, with the error rendered as a message.
+    Parse { position: Position, message: String },
+    /// More bytes were required than the buffer is limited to.
+    BufferLimit { required: usize, max: usize },
+}
+
+impl Display for ReadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReadError::Io(err) => write!(f, "read failed: {}", err),
+            ReadError::Parse { position, message } => {
+                write!(f, "{} at offset {}", message, position.offset())
+            }
+            ReadError::BufferLimit { required, max } => write!(
+                f,
+                "requires buffering {} bytes, more than the limit of {}",
+                required, max
+            ),
+        }
+    }
+}
+
+impl StdError for ReadError {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        match self {
+            ReadError::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for ReadError {
+    fn from(err: io::Error) -> Self {
+        ReadError::Io(err)
+    }
+}
+
+/// Bytes buffered from a reader, and how far they have been parsed.
+#[derive(Debug)]
+struct ReadBuffer {
+    bytes: Vec<u8>,
+    start: usize,
+    offset: usize,
+    max_buffer: usize,
+    eof: bool,
+    done: bool,
+    memo: ScanMemo,
+}
+
+/// What a driver must do next to continue parsing.
+enum Step<O> {
+    /// Yield an item, or nothing if parsing has finished.
+    Yield(Option<Result<O, ReadError>>),
+    /// Read until at least this many bytes are buffered.
+    Fill(usize),
+}
+
+impl ReadBuffer {
+    fn new() -> Self {
+        Self {
+            bytes: Vec::new(),
+            start: 0,
+            offset: 0,
+            max_buffer: DEFAULT_MAX_BUFFER,
+            eof: false,
+            done: false,
+            memo: ScanMemo::new(),
+        }
+    }
+
+    fn buffered(&self) -> &[u8] {
+        &self.bytes[self.start..]
+    }
+
+    /// Runs the parser over the unparsed bytes, if there are any.
+    fn step<F, O>(&mut self, parser: &F) -> Step<O>
+    where
+        F: for<'i> Fn(ReadPass<'i>) -> PassResult<'i, ReadPass<'i>, O>,
+    {
+        if self.done {
+            return Step::Yield(None);
+        }
+        let available = self.bytes.len() - self.start;
+        if available == 0 {
+            if self.eof {
+                self.done = true;
+                return Step::Yield(None);
+            }
+            return Step::Fill(1);
+        }
+        let tokens = &self.bytes[self.start..];
+        let input = if self.eof {
+            StreamInput::complete(tokens)
+        } else {
+            StreamInput::partial(tokens)
+        };
+        self.memo.restart();
+        let err = match parser(ReadPass::new(input, self.offset).with_memo(&self.memo)) {
+            Ok((out, pass)) => {
+                let consumed = pass.position().offset() - self.offset;
+                self.start += consumed;
+                self.offset += consumed;
+                self.memo.clear();
+                // A parser that consumes nothing would yield forever.
+                self.done = consumed == 0;
+                return Step::Yield(Some(Ok(out)));
+            }
+            Err((err, _)) => err,
+        };
+        match err.requirement() {
+            Some(requirement) if !self.eof => {
+                let min = requirement.minimum();
+                Step::Fill(available + min)
+            }
+            _ => {
+                let err = ReadError::Parse {
+                    position: err.position(),
+                    message: err.to_string(),
+                };
+                self.done = true;
+                Step::Yield(Some(Err(err)))
+            }
+        }
+    }
+
+    /// Returns whether `required` bytes are buffered, or the reader is
+    /// exhausted.
+    fn is_filled(&self, required: usize) -> bool {
+        self.eof || self.bytes.len() - self.start >= required
+    }
+
+    /// Makes room to read for `required` unparsed bytes, returning how many
+    /// bytes to read. The room is reserved, so the buffer only ever holds
+    /// bytes actually read.
+    fn reserve(&mut self, required: usize) -> Result<usize, ReadError> {
+        if required > self.max_buffer {
+            return Err(ReadError::BufferLimit {
+                required,
+                max: self.max_buffer,
+            });
+        }
+        if self.start > 0 {
+            self.bytes.drain(..self.start);
+            self.start = 0;
+        }
+        let filled = self.bytes.len();
+        let want = required.saturating_sub(filled).max(READ_CHUNK);
+        let want = (filled + want).min(self.max_buffer) - filled;
+        self.bytes.reserve_exact(want);
+        Ok(want)
+    }
+
+    /// Records the result of reading into the space after `filled` bytes.
+    fn advance(&mut self, filled: usize, read: io::Result<usize>) -> Result<(), ReadError> {
+        match read {
+            Ok(n) => {
+                self.bytes.truncate(filled + n);
+                self.eof = n == 0;
+                Ok(())
+            }
+            Err(ref err) if err.kind() == io::ErrorKind::Interrupted => {
+                self.bytes.truncate(filled);
+                Ok(())
+            }
+            Err(err) => {
+                self.bytes.truncate(filled);
+                Err(err.into())
+            }
+        }
+    }
+}
+
+/// Runs a parser repeatedly over bytes from a reader, yielding each output.
+///
+/// Bytes are buffered from the reader only as the parser requires them,
+/// growing the buffer by what an incomplete error requires, up to a maximum.
+/// Any reader works, though a `BufRead` avoids small reads to the source.
+///
+/// The parser must be able to run over passes of any lifetime, which a
+/// closure annotated as taking a `ReadPass<'_>` is.
+pub struct ReadParser<R, F> {
+    reader: R,
+    parser: F,
+    buffer: ReadBuffer,
+}
+
+impl<R, F, O> ReadParser<R, F>
+where
+    R: Read,
+    F: for<'i> Fn(ReadPass<'i>) -> PassResult<'i, ReadPass<'i>, O>,
+{
+    pub fn new(reader: R, parser: F) -> Self {
+        Self {
+            reader,
+            parser,
+            buffer: ReadBuffer::new(),
+        }
+    }
+
+    /// Limit how many bytes are buffered for a single parse.
+    pub fn with_max_buffer(mut self, max_buffer: usize) -> Self {
+        self.buffer.max_buffer = max_buffer;
+        self
+    }
+
+    /// Returns the bytes buffered but not yet parsed.
+    pub fn buffered(&self) -> &[u8] {
+        self.buffer.buffered()
+    }
+
+    /// Consume the parser, returning the reader.
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+
+    fn fill(&mut self, required: usize) -> Result<(), ReadError> {
+        while !self.buffer.is_filled(required) {
+            let want = self.buffer.reserve(required)?;
+            let filled = self.buffer.bytes.len();
+            self.buffer.bytes.resize(filled + want, 0);
+            let read = self.reader.read(&mut self.buffer.bytes[filled..]);
+            self.buffer.advance(filled, read)?;
+        }
+        Ok(())
+    }
+}
+
+impl<R, F, O> Iterator for ReadParser<R, F>
+where
+    R: Read,
+    F: for<'i> Fn(ReadPass<'i>) -> PassResult<'i, ReadPass<'i>, O>,
+{
+    type Item = Result<O, ReadError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            match self.buffer.step(&self.parser) {
+                Step::Yield(item) => return item,
+                Step::Fill(required) => {
+                    if let Err(err) = self.fill(required) {
+                        self.buffer.done = true;
+                        return Some(Err(err));
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ascii::*;
+    use crate::byte::*;
+    use crate::core::*;
+
+    use assert_matches::assert_matches;
+
+    /// A reader that returns at most one byte per read.
+    struct Trickle<'a>(&'a [u8]);
+
+    impl<'a> Read for Trickle<'a> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            match self.0.split_first() {
+                Some((byte, rest)) if !buf.is_empty() => {
+                    buf[0] = *byte;
+                    self.0 = rest;
+                    Ok(1)
+                }
+                _ => Ok(0),
+            }
+        }
+    }
+
+    #[test]
+    fn test_read_parser_items() {
+        let data = [0x00, 0x01, 0x00, 0x02, 0xff, 0xff];
+        let parser = ReadParser::new(Trickle(&data), |pass: ReadPass<'_>| be_u16(pass));
+
+        let items: Vec<_> = parser.map(Result::unwrap).collect();
+        assert_eq!(items, vec![1, 2, 0xffff]);
+    }
+
+    #[test]
+    fn test_read_parser_lines() {
+        let data = &b"12;345;6"[..];
+        let parser = ReadParser::new(data, |pass: ReadPass<'_>| {
+            terminated(many1(parse_ascii_digit), token(b';'))(pass)
+        });
+
+        let mut items = parser.into_iter();
+        assert_matches!(items.next(), Some(Ok(digits)) => assert_eq!(digits, vec![1, 2]));
+        assert_matches!(items.next(), Some(Ok(digits)) => assert_eq!(digits, vec![3, 4, 5]));
+        assert_matches!(items.next(), Some(Err(ReadError::Parse { position, .. })) => {
+            assert_eq!(position, Position::new(8));
+        });
+        assert_matches!(items.next(), None);
+    }
+
+    #[test]
+    fn test_read_parser_buffer_limit() {
+        let data = &b"aaaaaaaaaaaaaaaa"[..];
+        let parser = ReadParser::new(data, |pass: ReadPass<'_>| {
+            take_input(10)(pass).map(|(_, pass)| ((), pass))
+        })
+        .with_max_buffer(4);
+
+        let mut items = parser.into_iter();
+        assert_matches!(
+            items.next(),
+            Some(Err(ReadError::BufferLimit {
+                required: 10,
+                max: 4
+            }))
+        );
+        assert_matches!(items.next(), None);
+    }
+}
@@ -2,7 +2,10 @@
 mod macros;
 
 pub mod ascii;
+pub mod bits;
 pub mod byte;
 pub mod core;
 pub mod input;
+pub mod io;
 pub mod pass;
+pub mod utf8;
@@ -7,9 +7,7 @@ macro_rules! impl_complete_capture {
                 true
             }
 
-            fn resolve(&mut self) {
-                ()
-            }
+            fn resolve(&mut self) {}
 
             fn into_value(self) -> Self::Value {
                 self
@@ -0,0 +1,74 @@
+use std::marker::PhantomData;
+
+use super::{Context, Error, Pass, PassInput, Position};
+use crate::input::{BitInput, SliceInput};
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct BitPassContext<'i> {
+    input: BitInput<'i>,
+}
+
+impl<'i> Context<'i> for BitPassContext<'i> {
+    type Input = BitInput<'i>;
+
+    fn input(&self) -> Self::Input {
+        self.input
+    }
+
+    /// The offset is in bits from the start of the bytes.
+    fn position(&self) -> Position {
+        Position::new(self.input.offset())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct BitPass<'i, E>
+where
+    E: Error<'i>,
+{
+    ctx: BitPassContext<'i>,
+    _err: PhantomData<E>,
+}
+
+impl<'i, E> BitPass<'i, E>
+where
+    E: Error<'i, Context = BitPassContext<'i>>,
+{
+    /// Create a pass over the bits of an input.
+    pub fn new(input: BitInput<'i>) -> Self {
+        Self {
+            ctx: BitPassContext { input },
+            _err: PhantomData::<E>,
+        }
+    }
+}
+
+impl<'i, E> Pass<'i> for BitPass<'i, E>
+where
+    E: Error<'i, Context = BitPassContext<'i>>,
+{
+    type Error = E;
+    type Context = BitPassContext<'i>;
+
+    fn context(&self) -> &Self::Context {
+        &self.ctx
+    }
+
+    fn into_context(self) -> Self::Context {
+        self.ctx
+    }
+
+    fn commit(mut self, rest: PassInput<'i, Self>) -> Self {
+        self.ctx.input = rest;
+        self
+    }
+}
+
+impl<'i, E> From<&'i [u8]> for BitPass<'i, E>
+where
+    E: Error<'i, Context = BitPassContext<'i>>,
+{
+    fn from(bytes: &'i [u8]) -> Self {
+        Self::new(BitInput::complete(SliceInput::from(bytes)))
+    }
+}
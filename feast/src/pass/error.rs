@@ -1,7 +1,8 @@
-use super::{Context, ContextToken};
-use crate::input;
+use super::{Context, ContextToken, Position};
+use crate::input::{self, ErrorReason, ExpectedHint, Requirement};
 
-use std::fmt::Debug;
+use std::fmt::{self, Debug, Display};
+use std::mem;
 
 pub trait Error<'i>: Debug + 'i {
     type Context: Context<'i>;
@@ -9,6 +10,62 @@ pub trait Error<'i>: Debug + 'i {
 
     // Create pass error from input error.
     fn from_input(ctx: &Self::Context, err: Self::InputError) -> Self;
+
+    /// Merge with an error from an alternative that failed at the same point.
+    ///
+    /// By default the first error is kept.
+    fn merge(self, _other: Self) -> Self
+    where
+        Self: Sized,
+    {
+        self
+    }
+
+    /// Attach a description of what was being parsed when the error occurred.
+    ///
+    /// By default the description is dropped.
+    fn hint(self, _description: &'i str) -> Self
+    where
+        Self: Sized,
+    {
+        self
+    }
+
+    /// Attach a description of what was being parsed, never taken as what
+    /// was expected. By default it is attached as a hint.
+    fn push_context(self, description: &'i str) -> Self
+    where
+        Self: Sized,
+    {
+        self.hint(description)
+    }
+
+    /// Returns what is required to continue if the input was incomplete.
+    ///
+    /// By default nothing, so every error is fatal.
+    fn requirement(&self) -> Option<Requirement> {
+        None
+    }
+
+    /// If the error is fatal, we won't be able to try again with more input.
+    fn is_fatal(&self) -> bool {
+        self.requirement().is_none()
+    }
+
+    /// If the error is a failure, no alternative should be tried.
+    fn is_failure(&self) -> bool {
+        false
+    }
+
+    /// Turn the error into a failure, committing to the parse so far.
+    ///
+    /// By default the error is returned as is, never becoming a failure.
+    fn cut(self) -> Self
+    where
+        Self: Sized,
+    {
+        self
+    }
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -16,7 +73,31 @@ pub struct VerboseError<'i, C>
 where
     C: Context<'i>,
 {
-    input: input::ErrorReason<'i, ContextToken<'i, C>>,
+    input: ErrorReason<'i, ContextToken<'i, C>>,
+    position: Position,
+    // A boxed slice, smaller than a `Vec`, as parse results carry errors
+    // and most have no contexts.
+    contexts: Box<[&'i str]>,
+}
+
+impl<'i, C> VerboseError<'i, C>
+where
+    C: Context<'i>,
+{
+    /// Returns the reason for the error from the input.
+    pub fn reason(&self) -> &ErrorReason<'i, ContextToken<'i, C>> {
+        &self.input
+    }
+
+    /// Returns the position in the source where the error occurred.
+    pub fn position(&self) -> Position {
+        self.position
+    }
+
+    /// Returns the descriptions of what was being parsed, innermost first.
+    pub fn contexts(&self) -> &[&'i str] {
+        &self.contexts
+    }
 }
 
 impl<'i, C> Error<'i> for VerboseError<'i, C>
@@ -24,9 +105,107 @@ where
     C: Context<'i>,
 {
     type Context = C;
-    type InputError = input::ErrorReason<'i, ContextToken<'i, C>>;
+    type InputError = ErrorReason<'i, ContextToken<'i, C>>;
+
+    fn from_input(ctx: &Self::Context, err: Self::InputError) -> Self {
+        VerboseError {
+            input: err,
+            position: ctx.position(),
+            contexts: Box::default(),
+        }
+    }
+
+    fn merge(self, other: Self) -> Self {
+        VerboseError {
+            input: self.input.merge(other.input),
+            position: self.position,
+            contexts: self.contexts,
+        }
+    }
+
+    /// The first hint describes what was expected if nothing else did,
+    /// subsequent hints are kept as the contexts being parsed.
+    fn hint(mut self, description: &'i str) -> Self {
+        match self.input.unexpected_mut() {
+            Some(unexpected) if unexpected.expecting == ExpectedHint::None => {
+                unexpected.expecting = ExpectedHint::Description(description);
+                self
+            }
+            _ => self.push_context(description),
+        }
+    }
+
+    fn push_context(mut self, description: &'i str) -> Self {
+        let mut contexts = mem::take(&mut self.contexts).into_vec();
+        contexts.push(description);
+        self.contexts = contexts.into_boxed_slice();
+        self
+    }
+
+    fn requirement(&self) -> Option<Requirement> {
+        match self.input {
+            ErrorReason::Incomplete(ref requirement) => Some(requirement.clone()),
+            _ => None,
+        }
+    }
+
+    fn is_failure(&self) -> bool {
+        self.input.is_failure()
+    }
+
+    fn cut(mut self) -> Self {
+        self.input = self.input.cut();
+        self
+    }
+}
+
+impl<'i, C> Display for VerboseError<'i, C>
+where
+    C: Context<'i>,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, context) in self.contexts().iter().rev().enumerate() {
+            if i > 0 {
+                f.write_str(" > ")?;
+            }
+            write!(f, "while parsing {}", context)?;
+        }
+        if !self.contexts().is_empty() {
+            f.write_str(": ")?;
+        }
+        Display::fmt(&self.input, f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pass::SlicePassContext;
+
+    /// An error implementing only what is required.
+    #[derive(Debug, PartialEq)]
+    struct MinimalError;
+
+    impl<'i> Error<'i> for MinimalError {
+        type Context = SlicePassContext<'i, u8>;
+        type InputError = ErrorReason<'i, u8>;
+
+        fn from_input(_ctx: &Self::Context, _err: Self::InputError) -> Self {
+            MinimalError
+        }
+    }
+
+    #[test]
+    fn test_error_defaults() {
+        let err = <MinimalError as Error<'_>>::hint(MinimalError, "value");
 
-    fn from_input(_ctx: &Self::Context, err: Self::InputError) -> Self {
-        VerboseError { input: err }
+        assert_eq!(err, MinimalError);
+        assert_eq!(err.requirement(), None);
+        assert!(err.is_fatal());
+        assert!(!err.is_failure());
+        assert_eq!(
+            err.push_context("list").cut().merge(MinimalError),
+            MinimalError
+        );
     }
 }
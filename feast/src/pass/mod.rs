@@ -1,17 +1,44 @@
+mod bits;
 mod error;
+mod position;
+mod recovery;
+mod report;
+mod scan;
 mod slice;
+mod stream;
+mod text;
 
 use std::fmt::Debug;
 
-use crate::input::{self, Input, InputSection, InputToken, Requirement, Unexpected};
+use crate::input::{self, CustomError, Input, InputSection, InputToken, Requirement, Unexpected};
 
+pub use self::bits::*;
 pub use self::error::*;
+pub use self::position::*;
+pub use self::recovery::*;
+pub use self::report::*;
+pub use self::scan::*;
 pub use self::slice::*;
+pub use self::stream::*;
+pub use self::text::*;
 
 pub trait Context<'i>: Sized + Debug + 'i {
     type Input: Input<'i>;
 
     fn input(&self) -> Self::Input;
+
+    /// Returns the position of the input within the source.
+    ///
+    /// By default the start of the source, for contexts not tracking it.
+    fn position(&self) -> Position {
+        Position::default()
+    }
+
+    /// Returns the memo of searches through earlier, shorter input, if the
+    /// pass is re-run as more input arrives.
+    fn scan_memo(&self) -> Option<&ScanMemo> {
+        None
+    }
 }
 
 type ContextInput<'i, C> = <C as Context<'i>>::Input;
@@ -29,11 +56,24 @@ pub trait Pass<'i>: Sized + Debug + 'i {
     /// Commit the remaining input to be used, consuming the changes.
     fn commit(self, rest: PassInput<'i, Self>) -> Self;
 
+    /// Commit the remaining input found at `position` within the source,
+    /// for input that is not a suffix of the current input.
+    ///
+    /// By default the position is ignored, for passes not tracking it.
+    fn commit_at(self, rest: PassInput<'i, Self>, _position: Position) -> Self {
+        self.commit(rest)
+    }
+
     /// Get the input for this pass.
     fn input(&self) -> PassInput<'i, Self> {
         self.context().input()
     }
 
+    /// Get the position of this pass within the source.
+    fn position(&self) -> Position {
+        self.context().position()
+    }
+
     /// With input result, mapping the error for a pass.
     fn with_input_result<O>(
         self,
@@ -69,6 +109,13 @@ pub trait Pass<'i>: Sized + Debug + 'i {
             unexpected,
         ))
     }
+
+    /// Create a pass error based on a user-defined error.
+    fn with_input_error_custom(self, custom: CustomError) -> (PassError<'i, Self>, Self) {
+        self.with_input_error(<PassInputError<'i, Self> as input::Error<'i>>::custom(
+            custom,
+        ))
+    }
 }
 
 pub type PassError<'i, P> = <P as Pass<'i>>::Error;
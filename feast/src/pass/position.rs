@@ -0,0 +1,99 @@
+use crate::input::Token;
+
+/// A position within the source input of a pass.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Position {
+    offset: usize,
+}
+
+impl Position {
+    /// Create a position from an offset in tokens from the start of the source.
+    pub fn new(offset: usize) -> Self {
+        Self { offset }
+    }
+
+    /// Returns the offset in tokens from the start of the source.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Returns the position after committing from `len` tokens remaining to
+    /// `rest_len`, which may be more to backtrack.
+    pub(crate) fn commit(self, len: usize, rest_len: usize) -> Self {
+        Self::new((self.offset + len).saturating_sub(rest_len))
+    }
+
+    /// Returns the line and column of the position within the source.
+    ///
+    /// Lines are broken by `\n` tokens. Both the line and the column are
+    /// counted from one, with the column counted in tokens.
+    pub fn line_column<T>(&self, source: &[T]) -> LineColumn
+    where
+        T: Token,
+    {
+        let end = self.offset.min(source.len());
+        let mut line_column = LineColumn { line: 1, column: 1 };
+        for token in &source[..end] {
+            if token.clone().into_bytes().as_ref() == b"\n" {
+                line_column.line += 1;
+                line_column.column = 1;
+            } else {
+                line_column.column += 1;
+            }
+        }
+        line_column
+    }
+}
+
+/// A one-based line and column within a source.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct LineColumn {
+    pub line: usize,
+    pub column: usize,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::SliceInput;
+    use crate::pass::{Pass, SlicePass, SlicePassContext, VerboseError};
+
+    type TestPass = SlicePass<'static, u8, VerboseError<'static, SlicePassContext<'static, u8>>>;
+
+    #[test]
+    fn test_line_column() {
+        let source = b"ab\ncd\n\nef";
+
+        assert_eq!(
+            Position::new(0).line_column(source),
+            LineColumn { line: 1, column: 1 }
+        );
+        assert_eq!(
+            Position::new(4).line_column(source),
+            LineColumn { line: 2, column: 2 }
+        );
+        assert_eq!(
+            Position::new(8).line_column(source),
+            LineColumn { line: 4, column: 2 }
+        );
+
+        let chars: Vec<char> = "é\nü".chars().collect();
+        assert_eq!(
+            Position::new(2).line_column(&chars),
+            LineColumn { line: 2, column: 1 }
+        );
+    }
+
+    #[test]
+    fn test_commit_position() {
+        let pass = TestPass::from(&b"abcd"[..]);
+        let input = pass.input();
+
+        let pass = pass.commit(SliceInput::from(&b"cd"[..]));
+        assert_eq!(pass.position(), Position::new(2));
+        let pass = pass.commit(SliceInput::empty());
+        assert_eq!(pass.position(), Position::new(4));
+        let pass = pass.commit(input);
+        assert_eq!(pass.position(), Position::new(0));
+    }
+}
@@ -0,0 +1,147 @@
+use super::{Pass, PassError, PassInput, Position};
+
+/// A pass that keeps the errors it has recovered from.
+///
+/// Parsers that recover from an error record it on the pass before carrying
+/// on, so every error can be reported once parsing is done.
+pub trait Recover<'i>: Pass<'i> {
+    /// Records an error that was recovered from.
+    fn record(self, err: PassError<'i, Self>) -> Self;
+
+    /// Returns the errors recovered from so far, in the order they occurred.
+    fn diagnostics(&self) -> &[PassError<'i, Self>];
+}
+
+/// A pass collecting the errors recovered from while parsing.
+///
+/// Wraps another pass, parsing the same input. Errors are recorded with the
+/// position parsing resumed from, and dropped if a parser around the
+/// recovery backtracks before that position.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RecoveryPass<'i, P>
+where
+    P: Pass<'i>,
+{
+    pass: P,
+    diagnostics: Vec<P::Error>,
+    recorded: Vec<Position>,
+}
+
+impl<'i, P> RecoveryPass<'i, P>
+where
+    P: Pass<'i>,
+{
+    /// Create a pass collecting errors, parsing the same input as `pass`.
+    pub fn new(pass: P) -> Self {
+        Self {
+            pass,
+            diagnostics: Vec::new(),
+            recorded: Vec::new(),
+        }
+    }
+
+    /// Returns the pass wrapped.
+    pub fn into_inner(self) -> P {
+        self.pass
+    }
+
+    /// Returns the errors recovered from, in the order they occurred.
+    pub fn into_diagnostics(self) -> Vec<P::Error> {
+        self.diagnostics
+    }
+
+    /// Drops the errors recorded after the position of the pass.
+    fn forget_after(mut self) -> Self {
+        let position = self.pass.position();
+        let kept = self
+            .recorded
+            .partition_point(|&recorded| recorded <= position);
+        self.diagnostics.truncate(kept);
+        self.recorded.truncate(kept);
+        self
+    }
+}
+
+impl<'i, P> Pass<'i> for RecoveryPass<'i, P>
+where
+    P: Pass<'i>,
+{
+    type Context = P::Context;
+    type Error = P::Error;
+
+    fn context(&self) -> &Self::Context {
+        self.pass.context()
+    }
+
+    fn into_context(self) -> Self::Context {
+        self.pass.into_context()
+    }
+
+    fn commit(mut self, rest: PassInput<'i, Self>) -> Self {
+        self.pass = self.pass.commit(rest);
+        self.forget_after()
+    }
+
+    fn commit_at(mut self, rest: PassInput<'i, Self>, position: Position) -> Self {
+        self.pass = self.pass.commit_at(rest, position);
+        self.forget_after()
+    }
+}
+
+impl<'i, P> Recover<'i> for RecoveryPass<'i, P>
+where
+    P: Pass<'i>,
+{
+    fn record(mut self, err: PassError<'i, Self>) -> Self {
+        self.diagnostics.push(err);
+        self.recorded.push(self.pass.position());
+        self
+    }
+
+    fn diagnostics(&self) -> &[PassError<'i, Self>] {
+        &self.diagnostics
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::take_input;
+    use crate::input::{ErrorReason, Requirement, SliceInput};
+    use crate::pass::{Error, SlicePass, SlicePassContext, VerboseError};
+
+    type TestContext = SlicePassContext<'static, u8>;
+    type TestError = VerboseError<'static, TestContext>;
+    type TestPass = SlicePass<'static, u8, TestError>;
+
+    #[test]
+    fn test_recovery_pass_keeps_diagnostics() {
+        let pass = RecoveryPass::new(TestPass::from(&b"abc"[..]));
+        let (err, pass) = pass.with_input_error_incomplete(Requirement::Exact(1));
+        let pass = pass.record(err);
+        let (_, pass) = take_input(2)(pass).unwrap();
+
+        assert_eq!(pass.position().offset(), 2);
+        assert_eq!(pass.diagnostics().len(), 1);
+        assert_eq!(
+            pass.diagnostics()[0].reason(),
+            &ErrorReason::Incomplete(Requirement::Exact(1))
+        );
+        assert!(!pass.into_diagnostics()[0].is_fatal());
+    }
+
+    #[test]
+    fn test_recovery_pass_backtracks_diagnostics() {
+        let pass = RecoveryPass::new(TestPass::from(&b"abc"[..]));
+        let input = pass.input();
+        let (_, pass) = take_input(1)(pass).unwrap();
+        let (err, pass) = pass.with_input_error_incomplete(Requirement::Exact(1));
+        let pass = pass.record(err);
+        let (_, pass) = take_input(1)(pass).unwrap();
+
+        let pass = pass.commit(SliceInput::from(&b"bc"[..]));
+        assert_eq!(pass.diagnostics().len(), 1);
+        let pass = pass.commit(input);
+        assert!(pass.diagnostics().is_empty());
+    }
+}
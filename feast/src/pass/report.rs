@@ -0,0 +1,233 @@
+use std::fmt::{self, Display};
+use std::str;
+
+use super::{Context, ContextToken, VerboseError};
+use crate::input::{IntoBytes, Token};
+
+/// The number of tokens either side of an error shown in a text snippet.
+const TEXT_WINDOW: usize = 40;
+
+/// The number of bytes in a hex dump row.
+const HEX_ROW: usize = 16;
+
+/// A human readable report of an error with a snippet of the source.
+///
+/// Sources that are valid text around the error are shown as a line with
+/// a caret under the failure, otherwise a hex dump of the surrounding bytes
+/// is shown.
+pub struct Report<'a, 'i, C>
+where
+    C: Context<'i>,
+{
+    error: &'a VerboseError<'i, C>,
+    source: &'a [ContextToken<'i, C>],
+}
+
+impl<'i, C> VerboseError<'i, C>
+where
+    C: Context<'i>,
+{
+    /// Create a report of the error given the source it occurred in.
+    pub fn report<'a>(&'a self, source: &'a [ContextToken<'i, C>]) -> Report<'a, 'i, C> {
+        Report {
+            error: self,
+            source,
+        }
+    }
+}
+
+impl<'a, 'i, C> Report<'a, 'i, C>
+where
+    C: Context<'i>,
+{
+    fn offset(&self) -> usize {
+        self.error.position().offset().min(self.source.len())
+    }
+
+    /// Returns the line around the error, and the column of the error in
+    /// chars, if it is valid text.
+    fn text_line(&self) -> Option<(String, usize)> {
+        let offset = self.offset();
+        let is_newline = |token: &ContextToken<'i, C>| token.clone().into_bytes().as_ref() == b"\n";
+        let start = self.source[..offset]
+            .iter()
+            .rposition(is_newline)
+            .map(|i| i + 1)
+            .unwrap_or(0)
+            .max(offset.saturating_sub(TEXT_WINDOW));
+        let end = self.source[offset..]
+            .iter()
+            .position(is_newline)
+            .map(|i| offset + i)
+            .unwrap_or_else(|| self.source.len())
+            .min(offset.saturating_add(TEXT_WINDOW));
+        let prefix = to_text(&self.source[start..offset])?;
+        let rest = to_text(&self.source[offset..end])?;
+        let column = prefix.chars().count();
+        Some((prefix + &rest, column))
+    }
+
+    fn fmt_text(&self, f: &mut fmt::Formatter<'_>, line: &str, column: usize) -> fmt::Result {
+        let line_column = self.error.position().line_column(self.source);
+        let line_number = line_column.line.to_string();
+        let gutter = " ".repeat(line_number.len());
+        writeln!(
+            f,
+            "{} --> line {}, column {}",
+            gutter, line_column.line, line_column.column
+        )?;
+        writeln!(f, "{} |", gutter)?;
+        writeln!(f, "{} | {}", line_number, line)?;
+        write!(f, "{} | {}^", gutter, " ".repeat(column))
+    }
+
+    fn fmt_hex(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let offset = self.offset();
+        let row = offset / HEX_ROW;
+        let start = row.saturating_sub(1) * HEX_ROW;
+        write!(f, " --> offset {} (0x{:x})", offset, offset)?;
+        for row_start in (start..=row * HEX_ROW).step_by(HEX_ROW) {
+            let row_end = (row_start + HEX_ROW).min(self.source.len());
+            let bytes = to_bytes(&self.source[row_start..row_end]);
+            write!(f, "\n{:08x}  ", row_start)?;
+            for i in 0..HEX_ROW {
+                match bytes.get(i) {
+                    Some(byte) => write!(f, "{:02x} ", byte)?,
+                    None => f.write_str("   ")?,
+                }
+                if i == 7 {
+                    f.write_str(" ")?;
+                }
+            }
+            f.write_str(" |")?;
+            for byte in bytes.iter() {
+                let ch = *byte as char;
+                if ch.is_ascii_graphic() || ch == ' ' {
+                    write!(f, "{}", ch)?;
+                } else {
+                    f.write_str(".")?;
+                }
+            }
+            f.write_str("|")?;
+        }
+        let column = offset - row * HEX_ROW;
+        let indent = 10 + column * 3 + if column >= 8 { 1 } else { 0 };
+        write!(f, "\n{}^^", " ".repeat(indent))
+    }
+}
+
+impl<'a, 'i, C> Display for Report<'a, 'i, C>
+where
+    C: Context<'i>,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "error: {}", self.error)?;
+        match self.text_line() {
+            Some((line, column)) => self.fmt_text(f, &line, column),
+            None => self.fmt_hex(f),
+        }
+    }
+}
+
+fn to_bytes<T: Token>(tokens: &[T]) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(tokens.len() * T::byte_size().unwrap_or(1));
+    for token in tokens {
+        bytes.extend_from_slice(token.clone().into_bytes().as_ref());
+    }
+    bytes
+}
+
+/// Decodes tokens as text, replacing tabs with spaces.
+///
+/// Returns `None` if the tokens are not valid text.
+fn to_text<T: Token>(tokens: &[T]) -> Option<String> {
+    let bytes = to_bytes(tokens);
+    let text = str::from_utf8(&bytes).ok()?;
+    text.chars()
+        .map(|ch| match ch {
+            '\t' => Some(' '),
+            '\r' => Some(' '),
+            ch if ch.is_control() => None,
+            ch => Some(ch),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ascii::*;
+    use crate::byte::*;
+    use crate::core::*;
+    use crate::pass::{Pass, SlicePass, SlicePassContext, VerboseError};
+
+    use assert_matches::assert_matches;
+
+    type TestContext = SlicePassContext<'static, u8>;
+    type TestError = VerboseError<'static, TestContext>;
+    type TestPass = SlicePass<'static, u8, TestError>;
+
+    fn test_pass(input: &'static [u8]) -> TestPass {
+        TestPass::from(input)
+    }
+
+    #[test]
+    fn test_report_text() {
+        let version = hint(preceded(tag(b"v"), ascii_digit), "version");
+        let parser = preceded(tag(b"HDR\n"), version);
+
+        assert_matches!(
+            parser(test_pass(b"HDR\nv:1\n")),
+            Err((err, pass_out)) => {
+                assert_eq!(
+                    err.report(pass_out.context().source()).to_string(),
+                    concat!(
+                        "error: while parsing version: expected valid ascii decimal digit, found ':'\n",
+                        "  --> line 2, column 2\n",
+                        "  |\n",
+                        "2 | v:1\n",
+                        "  |  ^",
+                    )
+                );
+            }
+        );
+    }
+
+    #[test]
+    fn test_report_hex() {
+        let parser = preceded(be_u16, token(0xff));
+
+        assert_matches!(
+            parser(test_pass(&[0x00, 0x01, 0x02, 0x03])),
+            Err((err, pass_out)) => {
+                assert_eq!(
+                    err.report(pass_out.context().source()).to_string(),
+                    concat!(
+                        "error: unexpected 0x02\n",
+                        " --> offset 2 (0x2)\n",
+                        "00000000  00 01 02 03                                       |....|\n",
+                        "                ^^",
+                    )
+                );
+            }
+        );
+    }
+
+    #[test]
+    fn test_report_incomplete() {
+        assert_matches!(
+            tag(b"GET")(test_pass(b"GE")),
+            Err((err, pass_out)) => {
+                assert_eq!(
+                    err.report(pass_out.context().source()).to_string(),
+                    concat!(
+                        "error: incomplete input, requires 1 more token\n",
+                        "  --> line 1, column 1\n",
+                        "  |\n",
+                        "1 | GE\n",
+                        "  | ^",
+                    )
+                );
+            }
+        );
+    }
+}
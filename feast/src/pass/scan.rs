@@ -0,0 +1,92 @@
+use std::cell::{Cell, RefCell};
+
+use super::Position;
+
+#[derive(Clone, Debug, PartialEq)]
+struct ScanRecord {
+    search: usize,
+    position: Position,
+    scanned: usize,
+}
+
+/// Remembers how far searches got through incomplete input, so that re-runs
+/// of a parser over more input resume scanning where they left off.
+///
+/// Searches are identified by the order they start in within a run, and the
+/// position they start from. A driver re-running a parser from the same
+/// position must [`restart`](Self::restart) the memo before each run, and
+/// [`clear`](Self::clear) it once the parser succeeds.
+#[derive(Debug, Default)]
+pub struct ScanMemo {
+    searches: Cell<usize>,
+    records: RefCell<Vec<ScanRecord>>,
+}
+
+impl ScanMemo {
+    /// Create a memo with no searches remembered.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Prepare for a re-run of the parser, keeping what was remembered.
+    pub fn restart(&self) {
+        self.searches.set(0);
+    }
+
+    /// Forget every search, as the parser is to run from a new position.
+    pub fn clear(&self) {
+        self.searches.set(0);
+        self.records.borrow_mut().clear();
+    }
+
+    /// Start a search at a position, returning its identity and the number of
+    /// tokens already scanned by a previous run.
+    pub(crate) fn begin(&self, position: Position) -> (usize, usize) {
+        let search = self.searches.get();
+        self.searches.set(search + 1);
+        let scanned = self
+            .records
+            .borrow()
+            .iter()
+            .find(|record| record.search == search && record.position == position)
+            .map_or(0, |record| record.scanned);
+        (search, scanned)
+    }
+
+    /// Remember the tokens scanned by a search that ran out of input.
+    pub(crate) fn remember(&self, search: usize, position: Position, scanned: usize) {
+        let mut records = self.records.borrow_mut();
+        match records
+            .iter_mut()
+            .find(|record| record.search == search && record.position == position)
+        {
+            Some(record) => record.scanned = scanned,
+            None => records.push(ScanRecord {
+                search,
+                position,
+                scanned,
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scan_memo_resumes() {
+        let memo = ScanMemo::new();
+
+        assert_eq!(memo.begin(Position::new(4)), (0, 0));
+        assert_eq!(memo.begin(Position::new(9)), (1, 0));
+        memo.remember(1, Position::new(9), 12);
+
+        memo.restart();
+        assert_eq!(memo.begin(Position::new(4)), (0, 0));
+        assert_eq!(memo.begin(Position::new(9)), (1, 12));
+
+        memo.clear();
+        assert_eq!(memo.begin(Position::new(9)), (0, 0));
+    }
+}
@@ -1,14 +1,31 @@
 use std::marker::PhantomData;
 
-use super::{Context, Error, Pass, PassInput};
+use super::{Context, Error, LineColumn, Pass, PassInput, Position};
 use crate::input::{SliceInput, Token};
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug)]
 pub struct SlicePassContext<'i, T>
 where
     T: Token,
 {
+    source: &'i [T],
     input: SliceInput<'i, T>,
+    position: Position,
+}
+
+impl<'i, T> SlicePassContext<'i, T>
+where
+    T: Token,
+{
+    /// Returns the whole slice the pass started with.
+    pub fn source(&self) -> &'i [T] {
+        self.source
+    }
+
+    /// Returns the line and column of the input within the source.
+    pub fn line_column(&self) -> LineColumn {
+        self.position().line_column(self.source)
+    }
 }
 
 impl<'i, T> Context<'i> for SlicePassContext<'i, T>
@@ -20,6 +37,20 @@ where
     fn input(&self) -> Self::Input {
         self.input.clone()
     }
+
+    fn position(&self) -> Position {
+        self.position
+    }
+}
+
+/// Contexts are equal if their remaining input is, regardless of source.
+impl<'i, T> PartialEq for SlicePassContext<'i, T>
+where
+    T: Token,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.input == other.input
+    }
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -49,6 +80,16 @@ where
     }
 
     fn commit(mut self, rest: PassInput<'i, Self>) -> Self {
+        self.ctx.position = self
+            .ctx
+            .position
+            .commit(self.ctx.input.0.len(), rest.0.len());
+        self.ctx.input = rest;
+        self
+    }
+
+    fn commit_at(mut self, rest: PassInput<'i, Self>, position: Position) -> Self {
+        self.ctx.position = position;
         self.ctx.input = rest;
         self
     }
@@ -62,7 +103,9 @@ where
     fn from(slice: &'i [T]) -> Self {
         Self {
             ctx: SlicePassContext {
+                source: slice,
                 input: SliceInput::from(slice),
+                position: Position::default(),
             },
             _err: PhantomData::<E>,
         }
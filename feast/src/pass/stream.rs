@@ -0,0 +1,387 @@
+use std::marker::PhantomData;
+
+use super::{Context, Error, Pass, PassInput, PassResult, Position, ScanMemo};
+use crate::input::{Requirement, StreamInput, Token};
+
+#[derive(Clone, Debug)]
+pub struct StreamPassContext<'i, T>
+where
+    T: Token,
+{
+    source: &'i [T],
+    input: StreamInput<'i, T>,
+    position: Position,
+    memo: Option<&'i ScanMemo>,
+}
+
+impl<'i, T> StreamPassContext<'i, T>
+where
+    T: Token,
+{
+    /// Returns the buffered tokens the pass started with.
+    pub fn source(&self) -> &'i [T] {
+        self.source
+    }
+}
+
+impl<'i, T> Context<'i> for StreamPassContext<'i, T>
+where
+    T: Token,
+{
+    type Input = StreamInput<'i, T>;
+
+    fn input(&self) -> Self::Input {
+        self.input.clone()
+    }
+
+    /// The position is from the start of the stream, not of the buffer.
+    fn position(&self) -> Position {
+        self.position
+    }
+
+    fn scan_memo(&self) -> Option<&ScanMemo> {
+        self.memo
+    }
+}
+
+/// Contexts are equal if their remaining input is, regardless of source.
+impl<'i, T> PartialEq for StreamPassContext<'i, T>
+where
+    T: Token,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.input == other.input
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct StreamPass<'i, T, E>
+where
+    T: Token,
+    E: Error<'i>,
+{
+    ctx: StreamPassContext<'i, T>,
+    _err: PhantomData<E>,
+}
+
+impl<'i, T, E> StreamPass<'i, T, E>
+where
+    T: Token,
+    E: Error<'i, Context = StreamPassContext<'i, T>>,
+{
+    /// Create a pass over buffered tokens of a stream, starting at an
+    /// offset within the stream.
+    pub fn new(input: StreamInput<'i, T>, base: usize) -> Self {
+        Self {
+            ctx: StreamPassContext {
+                source: input.tokens(),
+                input,
+                position: Position::new(base),
+                memo: None,
+            },
+            _err: PhantomData::<E>,
+        }
+    }
+
+    /// Resume searches remembered from earlier runs over a prefix of the
+    /// tokens, and remember those that run out of tokens.
+    pub fn with_memo(mut self, memo: &'i ScanMemo) -> Self {
+        self.ctx.memo = Some(memo);
+        self
+    }
+}
+
+impl<'i, T, E> Pass<'i> for StreamPass<'i, T, E>
+where
+    T: Token,
+    E: Error<'i, Context = StreamPassContext<'i, T>>,
+{
+    type Error = E;
+    type Context = StreamPassContext<'i, T>;
+
+    fn context(&self) -> &Self::Context {
+        &self.ctx
+    }
+
+    fn into_context(self) -> Self::Context {
+        self.ctx
+    }
+
+    fn commit(mut self, rest: PassInput<'i, Self>) -> Self {
+        let len = self.ctx.input.tokens().len();
+        self.ctx.position = self.ctx.position.commit(len, rest.tokens().len());
+        self.ctx.input = rest;
+        self
+    }
+
+    fn commit_at(mut self, rest: PassInput<'i, Self>, position: Position) -> Self {
+        self.ctx.position = position;
+        self.ctx.input = rest;
+        self
+    }
+}
+
+/// Buffers a stream, running a parser as more tokens arrive.
+///
+/// When a parser fails with incomplete input, it is re-run from the start of
+/// the unparsed tokens once enough have arrived to satisfy its requirement.
+/// Searches through the tokens resume where they ran out, rather than
+/// scanning the same tokens again.
+#[derive(Debug)]
+pub struct StreamParser<T>
+where
+    T: Token,
+{
+    buffer: Vec<T>,
+    start: usize,
+    offset: usize,
+    finished: bool,
+    pending: Option<(usize, Requirement)>,
+    memo: ScanMemo,
+}
+
+impl<T> StreamParser<T>
+where
+    T: Token,
+{
+    /// Create a parser for a stream with nothing yet buffered.
+    pub fn new() -> Self {
+        Self {
+            buffer: Vec::new(),
+            start: 0,
+            offset: 0,
+            finished: false,
+            pending: None,
+            memo: ScanMemo::new(),
+        }
+    }
+
+    /// Add tokens that have arrived from the stream.
+    pub fn feed(&mut self, tokens: &[T]) {
+        if self.start > 0 {
+            self.buffer.drain(..self.start);
+            self.start = 0;
+        }
+        self.buffer.extend_from_slice(tokens);
+    }
+
+    /// Mark the stream as finished, with no more tokens to arrive.
+    pub fn finish(&mut self) {
+        self.finished = true;
+    }
+
+    /// Returns whether the stream is finished and every token was parsed.
+    pub fn is_done(&self) -> bool {
+        self.finished && self.start == self.buffer.len()
+    }
+
+    /// Returns the tokens buffered but not yet parsed.
+    pub fn buffered(&self) -> &[T] {
+        &self.buffer[self.start..]
+    }
+
+    /// Returns what the parser last required to make progress, if it failed
+    /// with incomplete input.
+    pub fn requirement(&self) -> Option<&Requirement> {
+        self.pending.as_ref().map(|(_, requirement)| requirement)
+    }
+
+    /// Runs a parser over the unparsed tokens.
+    ///
+    /// Returns `Ok(None)` if more tokens are required, in which case the
+    /// parser is not re-run until at least the minimum required have been
+    /// fed. Once the stream is finished, incomplete errors are returned.
+    ///
+    /// The requirement and searches remembered after `Ok(None)` are those of
+    /// the parser given, so the same parser must be given again until any
+    /// other result. Any other result forgets them, after which a different
+    /// parser may be given.
+    pub fn parse<'s, F, O, E>(&'s mut self, parser: F) -> Result<Option<O>, E>
+    where
+        F: Fn(StreamPass<'s, T, E>) -> PassResult<'s, StreamPass<'s, T, E>, O>,
+        E: Error<'s, Context = StreamPassContext<'s, T>>,
+    {
+        let available = self.buffer.len() - self.start;
+        if let Some((buffered, ref requirement)) = self.pending {
+            let min = requirement.minimum();
+            if !self.finished && available < buffered + min {
+                return Ok(None);
+            }
+        }
+        let tokens = &self.buffer[self.start..];
+        let input = if self.finished {
+            StreamInput::complete(tokens)
+        } else {
+            StreamInput::partial(tokens)
+        };
+        self.memo.restart();
+        match parser(StreamPass::new(input, self.offset).with_memo(&self.memo)) {
+            Ok((out, pass)) => {
+                let consumed = pass.position().offset() - self.offset;
+                self.start += consumed;
+                self.offset += consumed;
+                self.pending = None;
+                self.memo.clear();
+                Ok(Some(out))
+            }
+            Err((err, _)) => match err.requirement() {
+                Some(requirement) if !self.finished => {
+                    self.pending = Some((available, requirement));
+                    Ok(None)
+                }
+                _ => {
+                    self.pending = None;
+                    self.memo.clear();
+                    Err(err)
+                }
+            },
+        }
+    }
+}
+
+impl<T> Default for StreamParser<T>
+where
+    T: Token,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ascii::*;
+    use crate::byte::*;
+    use crate::core::*;
+    use crate::input::{Capture, Input, Requirement};
+    use crate::pass::{PassSection, VerboseError};
+
+    use assert_matches::assert_matches;
+
+    type TestError<'i> = VerboseError<'i, StreamPassContext<'i, u8>>;
+
+    fn digits<'i, P>(pass: P) -> PassResult<'i, P, Vec<u8>>
+    where
+        P: Pass<'i>,
+        PassInput<'i, P>: Input<'i, Token = u8>,
+    {
+        terminated(many1(parse_ascii_digit), token(b';'))(pass)
+    }
+
+    fn line<'i, P>(pass: P) -> PassResult<'i, P, PassSection<'i, P>>
+    where
+        P: Pass<'i>,
+        PassInput<'i, P>: Input<'i, Token = u8>,
+    {
+        take_input_until(|token: &u8| *token == b'\n')(pass)
+    }
+
+    #[test]
+    fn test_stream_parser_resumes() {
+        let mut stream = StreamParser::new();
+
+        stream.feed(&[0x00, 0x01, 0x00]);
+        assert_matches!(
+            stream.parse::<_, _, TestError<'_>>(preceded(be_u16, be_u32)),
+            Ok(None)
+        );
+        assert_eq!(stream.requirement(), Some(&Requirement::Exact(3)));
+
+        stream.feed(&[0x00]);
+        assert_matches!(
+            stream.parse::<_, _, TestError<'_>>(preceded(be_u16, be_u32)),
+            Ok(None)
+        );
+
+        stream.feed(&[0x00, 0x2a, 0xff]);
+        assert_matches!(
+            stream.parse::<_, _, TestError<'_>>(preceded(be_u16, be_u32)),
+            Ok(Some(42))
+        );
+        assert_eq!(stream.buffered(), &[0xff]);
+    }
+
+    #[test]
+    fn test_stream_parser_positions() {
+        let mut stream = StreamParser::new();
+
+        stream.feed(b"12a");
+        assert_matches!(
+            stream.parse::<_, _, TestError<'_>>(ascii_digit),
+            Ok(Some(b'1'))
+        );
+        assert_matches!(
+            stream.parse::<_, _, TestError<'_>>(ascii_digit),
+            Ok(Some(b'2'))
+        );
+        assert_matches!(
+            stream.parse::<_, _, TestError<'_>>(ascii_digit),
+            Err(err) => {
+                assert_eq!(err.position(), Position::new(2));
+            }
+        );
+    }
+
+    #[test]
+    fn test_stream_parser_repetition_waits() {
+        let mut stream = StreamParser::new();
+
+        stream.feed(b"12");
+        assert_matches!(stream.parse::<_, _, TestError<'_>>(digits), Ok(None));
+
+        stream.feed(b"3;");
+        assert_matches!(
+            stream.parse::<_, _, TestError<'_>>(digits),
+            Ok(Some(digits)) => assert_eq!(digits, vec![1, 2, 3])
+        );
+        assert!(stream.buffered().is_empty());
+    }
+
+    #[test]
+    fn test_stream_parser_finish() {
+        let mut stream = StreamParser::new();
+
+        stream.feed(b"12");
+        assert_matches!(
+            stream.parse::<_, _, TestError<'_>>(many0(ascii_digit)),
+            Ok(None)
+        );
+
+        stream.finish();
+        assert_matches!(
+            stream.parse::<_, _, TestError<'_>>(many0(ascii_digit)),
+            Ok(Some(digits)) => assert_eq!(digits, b"12".to_vec())
+        );
+        assert!(stream.is_done());
+    }
+
+    #[test]
+    fn test_stream_parser_forgets_on_error() {
+        let mut stream = StreamParser::new();
+
+        stream.feed(b"ab");
+        assert_matches!(stream.parse::<_, _, TestError<'_>>(be_u32), Ok(None));
+        assert_eq!(stream.requirement(), Some(&Requirement::Exact(2)));
+
+        stream.finish();
+        assert_matches!(stream.parse::<_, _, TestError<'_>>(be_u32), Err(_));
+        assert_eq!(stream.requirement(), None);
+        assert_matches!(
+            stream.parse::<_, _, TestError<'_>>(be_u16),
+            Ok(Some(0x6162))
+        );
+    }
+
+    #[test]
+    fn test_stream_indeterminate_section() {
+        let mut stream = StreamParser::new();
+
+        stream.feed(b"abc\nde");
+        assert_matches!(
+            stream.parse::<_, _, TestError<'_>>(line),
+            Ok(Some(section)) => assert!(section.is_complete())
+        );
+        assert_matches!(stream.parse::<_, _, TestError<'_>>(line), Ok(None));
+    }
+}
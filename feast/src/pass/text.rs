@@ -0,0 +1,169 @@
+use std::marker::PhantomData;
+
+use super::{Context, Error, LineColumn, Pass, PassInput, Position};
+use crate::input::{ExactSizeInput, StrInput};
+
+#[derive(Clone, Debug)]
+pub struct StrPassContext<'i> {
+    source: &'i str,
+    input: StrInput<'i>,
+    position: Position,
+}
+
+impl<'i> StrPassContext<'i> {
+    /// Returns the whole string the pass started with.
+    pub fn source(&self) -> &'i str {
+        self.source
+    }
+
+    /// Returns the line and column of the input within the source, with
+    /// the column counted in chars.
+    pub fn line_column(&self) -> LineColumn {
+        let mut line_column = LineColumn { line: 1, column: 1 };
+        for token in self.source.chars().take(self.position.offset()) {
+            if token == '\n' {
+                line_column.line += 1;
+                line_column.column = 1;
+            } else {
+                line_column.column += 1;
+            }
+        }
+        line_column
+    }
+}
+
+impl<'i> Context<'i> for StrPassContext<'i> {
+    type Input = StrInput<'i>;
+
+    fn input(&self) -> Self::Input {
+        self.input
+    }
+
+    /// The offset is in chars from the start of the source.
+    fn position(&self) -> Position {
+        self.position
+    }
+}
+
+/// Contexts are equal if their remaining input is, regardless of source.
+impl<'i> PartialEq for StrPassContext<'i> {
+    fn eq(&self, other: &Self) -> bool {
+        self.input == other.input
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct StrPass<'i, E>
+where
+    E: Error<'i>,
+{
+    ctx: StrPassContext<'i>,
+    _err: PhantomData<E>,
+}
+
+impl<'i, E> Pass<'i> for StrPass<'i, E>
+where
+    E: Error<'i, Context = StrPassContext<'i>>,
+{
+    type Error = E;
+    type Context = StrPassContext<'i>;
+
+    fn context(&self) -> &Self::Context {
+        &self.ctx
+    }
+
+    fn into_context(self) -> Self::Context {
+        self.ctx
+    }
+
+    fn commit(mut self, rest: PassInput<'i, Self>) -> Self {
+        self.ctx.position = self.ctx.position.commit(self.ctx.input.len(), rest.len());
+        self.ctx.input = rest;
+        self
+    }
+
+    fn commit_at(mut self, rest: PassInput<'i, Self>, position: Position) -> Self {
+        self.ctx.position = position;
+        self.ctx.input = rest;
+        self
+    }
+}
+
+impl<'i, E> From<&'i str> for StrPass<'i, E>
+where
+    E: Error<'i, Context = StrPassContext<'i>>,
+{
+    fn from(text: &'i str) -> Self {
+        Self {
+            ctx: StrPassContext {
+                source: text,
+                input: StrInput::from(text),
+                position: Position::default(),
+            },
+            _err: PhantomData::<E>,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::*;
+    use crate::input::{ErrorReason, ExpectedHint, StrInput, TokenTag, Unexpected};
+    use crate::pass::VerboseError;
+
+    use assert_matches::assert_matches;
+
+    type TestError = VerboseError<'static, StrPassContext<'static>>;
+    type TestPass = StrPass<'static, TestError>;
+
+    fn test_pass(input: &'static str) -> TestPass {
+        TestPass::from(input)
+    }
+
+    #[test]
+    fn test_str_pass_tokens() {
+        let word = take_input_until(|token: &char| !token.is_alphabetic());
+
+        assert_matches!(
+            word(test_pass("grüße! x")),
+            Ok((out, pass_out)) => {
+                assert_eq!(out, StrInput::from("grüße!"));
+                assert_eq!(pass_out, test_pass(" x"));
+                assert_eq!(pass_out.position(), Position::new(6));
+            }
+        );
+    }
+
+    #[test]
+    fn test_str_pass_tag() {
+        let arrow = tag(&['→']);
+
+        assert_matches!(preceded(arrow, token('x'))(test_pass("→x")), Ok(('x', _)));
+        assert_matches!(
+            tag(&['→'])(test_pass("->")),
+            Err((err, _)) => {
+                assert_eq!(err.reason(), &ErrorReason::Unexpected(Unexpected {
+                    unexpected: TokenTag::Token('-'),
+                    expecting: ExpectedHint::Tag(&['→']),
+                }));
+            }
+        );
+    }
+
+    #[test]
+    fn test_str_pass_line_column() {
+        let lines = many0(terminated(many0(token('é')), token('\n')));
+
+        assert_matches!(
+            preceded(lines, many0(token('é')))(test_pass("é\néé\néy")),
+            Ok((_, pass_out)) => {
+                assert_eq!(pass_out.position(), Position::new(6));
+                assert_eq!(
+                    pass_out.context().line_column(),
+                    LineColumn { line: 3, column: 2 }
+                );
+            }
+        );
+    }
+}
@@ -0,0 +1,253 @@
+use std::ops::RangeInclusive;
+use std::str;
+
+use crate::input::{AsSlice, Capture, ExpectedHint, Input, Requirement, TokenTag, Unexpected};
+use crate::pass::{Pass, PassError, PassInput, PassResult};
+
+const CONTINUATION: RangeInclusive<u8> = 0x80..=0xbf;
+
+/// Returns the length of the sequence started by a leading byte, and the
+/// range its second byte must fall within.
+///
+/// The second byte's range excludes overlong encodings, surrogates, and
+/// scalar values past `U+10FFFF`.
+fn sequence_len(first: u8) -> Option<(usize, RangeInclusive<u8>)> {
+    match first {
+        0x00..=0x7f => Some((1, CONTINUATION)),
+        0xc2..=0xdf => Some((2, CONTINUATION)),
+        0xe0 => Some((3, 0xa0..=0xbf)),
+        0xed => Some((3, 0x80..=0x9f)),
+        0xe1..=0xef => Some((3, CONTINUATION)),
+        0xf0 => Some((4, 0x90..=0xbf)),
+        0xf1..=0xf3 => Some((4, CONTINUATION)),
+        0xf4 => Some((4, 0x80..=0x8f)),
+        _ => None,
+    }
+}
+
+fn invalid<'i, P>(pass: P, byte: u8) -> (PassError<'i, P>, P)
+where
+    P: Pass<'i>,
+    PassInput<'i, P>: Input<'i, Token = u8>,
+{
+    pass.with_input_error_unexpected(Unexpected {
+        unexpected: TokenTag::Token(byte),
+        expecting: ExpectedHint::Description("valid utf-8"),
+    })
+}
+
+/// Decodes a UTF-8 encoded scalar value as a `char`.
+///
+/// A sequence cut short by the end of input requires exactly the bytes it
+/// is missing. Invalid sequences, including overlong encodings and
+/// surrogates, are unexpected at the first byte that cannot continue them.
+pub fn utf8_char<'i, P>(pass: P) -> PassResult<'i, P, char>
+where
+    P: Pass<'i>,
+    PassInput<'i, P>: Input<'i, Token = u8>,
+{
+    let input = pass.input();
+    let mut tokens = input.iter();
+    let first = match tokens.next() {
+        Some(first) => first,
+        None => return Err(pass.with_input_error_incomplete(Requirement::Exact(1))),
+    };
+    let (len, second) = match sequence_len(first) {
+        Some(sequence) => sequence,
+        None => return Err(invalid(pass, first)),
+    };
+    let mut bytes = [first, 0, 0, 0];
+    for (i, slot) in bytes.iter_mut().enumerate().take(len).skip(1) {
+        let byte = match tokens.next() {
+            Some(byte) => byte,
+            None => return Err(pass.with_input_error_incomplete(Requirement::Exact(len - i))),
+        };
+        let range = if i == 1 { &second } else { &CONTINUATION };
+        if !range.contains(&byte) {
+            return Err(invalid(pass, byte));
+        }
+        *slot = byte;
+    }
+    match str::from_utf8(&bytes[..len])
+        .ok()
+        .and_then(|s| s.chars().next())
+    {
+        Some(ch) => {
+            let ((_, rest), pass) = pass.with_input_result(input.split_at(len))?;
+            Ok((ch, pass.commit(rest)))
+        }
+        None => Err(invalid(pass, first)),
+    }
+}
+
+/// Consumes the longest run of valid UTF-8 as a `&str`, which may be empty.
+///
+/// If the run reaches the end of an incomplete input, at least one more byte
+/// is required to know where it ends.
+pub fn utf8_run<'i, P>(pass: P) -> PassResult<'i, P, &'i str>
+where
+    P: Pass<'i>,
+    PassInput<'i, P>: Input<'i, Token = u8> + AsSlice<'i>,
+{
+    let input = pass.input();
+    let bytes = input.as_slice();
+    let (text, reaches_end) = match str::from_utf8(bytes) {
+        Ok(text) => (text, true),
+        Err(err) => {
+            let text = str::from_utf8(&bytes[..err.valid_up_to()]).unwrap_or_default();
+            (text, err.error_len().is_none())
+        }
+    };
+    if reaches_end && !input.is_complete() {
+        return Err(pass.with_input_error_incomplete(Requirement::AtLeast(1)));
+    }
+    let ((_, rest), pass) = pass.with_input_result(input.split_at(text.len()))?;
+    Ok((text, pass.commit(rest)))
+}
+
+/// Consumes exactly `len` bytes as a `&str`.
+///
+/// If the bytes are not valid UTF-8, the first byte of the invalid sequence
+/// is unexpected.
+pub fn utf8_str<'i, P>(len: usize) -> impl Fn(P) -> PassResult<'i, P, &'i str>
+where
+    P: Pass<'i>,
+    PassInput<'i, P>: Input<'i, Token = u8> + AsSlice<'i>,
+{
+    move |pass: P| {
+        let input = pass.input();
+        let bytes = input.as_slice();
+        let ((_, rest), pass) = pass.with_input_result(input.split_at(len))?;
+        match str::from_utf8(&bytes[..len]) {
+            Ok(text) => Ok((text, pass.commit(rest))),
+            Err(err) => Err(invalid(pass, bytes[err.valid_up_to()])),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::{ErrorReason, StreamInput};
+    use crate::pass::{
+        Error, SlicePass, SlicePassContext, StreamPass, StreamPassContext, VerboseError,
+    };
+
+    use assert_matches::assert_matches;
+
+    type TestContext = SlicePassContext<'static, u8>;
+    type TestError = VerboseError<'static, TestContext>;
+    type TestPass = SlicePass<'static, u8, TestError>;
+
+    fn test_pass(input: &'static [u8]) -> TestPass {
+        TestPass::from(input)
+    }
+
+    fn empty_pass() -> TestPass {
+        test_pass(b"")
+    }
+
+    fn unexpected(byte: u8) -> ErrorReason<'static, u8> {
+        ErrorReason::Unexpected(Unexpected {
+            unexpected: TokenTag::Token(byte),
+            expecting: ExpectedHint::Description("valid utf-8"),
+        })
+    }
+
+    #[test]
+    fn test_utf8_char() {
+        for text in &["a", "é", "€", "😀"] {
+            assert_matches!(
+                utf8_char(test_pass(text.as_bytes())),
+                Ok((ch, pass_out)) => {
+                    assert_eq!(Some(ch), text.chars().next());
+                    assert_eq!(pass_out, empty_pass());
+                }
+            );
+        }
+    }
+
+    #[test]
+    fn test_utf8_char_truncated() {
+        assert_matches!(
+            utf8_char(test_pass(&[0xe2, 0x82])),
+            Err((err, _)) => {
+                assert_eq!(err.reason(), &ErrorReason::Incomplete(Requirement::Exact(1)));
+            }
+        );
+        assert_matches!(
+            utf8_char(test_pass(&[0xf0])),
+            Err((err, _)) => {
+                assert_eq!(err.reason(), &ErrorReason::Incomplete(Requirement::Exact(3)));
+            }
+        );
+    }
+
+    #[test]
+    fn test_utf8_char_invalid() {
+        // Overlong encoding of '/'.
+        assert_matches!(
+            utf8_char(test_pass(&[0xc0, 0xaf])),
+            Err((err, _)) => assert_eq!(err.reason(), &unexpected(0xc0))
+        );
+        // Surrogate U+D800.
+        assert_matches!(
+            utf8_char(test_pass(&[0xed, 0xa0, 0x80])),
+            Err((err, _)) => assert_eq!(err.reason(), &unexpected(0xa0))
+        );
+        assert_matches!(
+            utf8_char(test_pass(&[0xe2, 0x28, 0xac])),
+            Err((err, pass_out)) => {
+                assert!(err.is_fatal());
+                assert_eq!(err.reason(), &unexpected(0x28));
+                assert_eq!(pass_out, test_pass(&[0xe2, 0x28, 0xac]));
+            }
+        );
+    }
+
+    #[test]
+    fn test_utf8_run() {
+        assert_matches!(
+            utf8_run(test_pass(b"h\xc3\xa9llo\xffx")),
+            Ok((text, pass_out)) => {
+                assert_eq!(text, "héllo");
+                assert_eq!(pass_out, test_pass(b"\xffx"));
+            }
+        );
+        assert_matches!(utf8_run(test_pass(b"abc\xe2\x82")), Ok(("abc", _)));
+    }
+
+    #[test]
+    fn test_utf8_run_stream() {
+        type StreamError = VerboseError<'static, StreamPassContext<'static, u8>>;
+        let partial = StreamPass::<u8, StreamError>::new(StreamInput::partial(b"ab\xe2"), 0);
+
+        assert_matches!(
+            utf8_run(partial),
+            Err((err, _)) => {
+                assert_eq!(err.reason(), &ErrorReason::Incomplete(Requirement::AtLeast(1)));
+            }
+        );
+
+        let partial = StreamPass::<u8, StreamError>::new(StreamInput::partial(b"ab\xff"), 0);
+        assert_matches!(utf8_run(partial), Ok(("ab", _)));
+    }
+
+    #[test]
+    fn test_utf8_str() {
+        assert_matches!(
+            utf8_str(3)(test_pass("€x".as_bytes())),
+            Ok(("€", pass_out)) => assert_eq!(pass_out, test_pass(b"x"))
+        );
+        assert_matches!(
+            utf8_str(3)(test_pass(b"a\xc3\x28")),
+            Err((err, _)) => assert_eq!(err.reason(), &unexpected(0xc3))
+        );
+        assert_matches!(
+            utf8_str(4)(test_pass(b"abc")),
+            Err((err, _)) => {
+                assert_eq!(err.reason(), &ErrorReason::Incomplete(Requirement::Exact(1)));
+            }
+        );
+    }
+}